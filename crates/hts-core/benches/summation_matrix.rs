@@ -0,0 +1,123 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Benchmarks for the summation matrix path on synthetic hierarchies.
+//!
+//! Run with `cargo bench -p hts-core`.
+
+#![allow(missing_docs)]
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use hts_core::testing::{random_bottom_data, random_hierarchy};
+use hts_core::{HierarchyTree, ReconMethod, ReconciliationMatrix, SummationMatrix};
+
+/// Synthetic hierarchy sizes: (name, branching factors, group sizes).
+const SIZES: &[(&str, &[usize], &[usize])] = &[
+    ("small", &[2, 3], &[2]),
+    ("medium", &[4, 5], &[3]),
+    ("large", &[6, 8], &[4]),
+];
+
+const PERIODS: usize = 8;
+const SEED: u64 = 42;
+
+fn bench_tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hierarchy_tree");
+    group.sample_size(10);
+
+    for &(name, levels, groups) in SIZES {
+        let (df, spec) = random_bottom_data(levels, groups, PERIODS, SEED);
+        group.bench_with_input(BenchmarkId::new("from_dataframe", name), &df, |b, df| {
+            b.iter(|| HierarchyTree::from_dataframe(black_box(df), &spec).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_summation_matrix(c: &mut Criterion) {
+    let mut group = c.benchmark_group("summation_matrix");
+
+    for &(name, levels, groups) in SIZES {
+        let (df, spec) = random_bottom_data(levels, groups, PERIODS, SEED);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+        let bottom: Vec<f64> = (0..s.n_bottom()).map(|i| i as f64).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("from_hierarchy", name),
+            &tree,
+            |b, tree| b.iter(|| SummationMatrix::from_hierarchy(black_box(tree))),
+        );
+        group.bench_with_input(BenchmarkId::new("aggregate", name), &bottom, |b, bottom| {
+            b.iter(|| s.aggregate(black_box(bottom)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_reconciliation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reconciliation");
+    group.sample_size(10);
+
+    for &(name, levels, groups) in SIZES {
+        let (df, spec) = random_bottom_data(levels, groups, PERIODS, SEED);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+        let y_hat: Vec<f64> = (0..s.n_series()).map(|i| i as f64).collect();
+
+        for method in [ReconMethod::Ols, ReconMethod::WlsStructural] {
+            let id = format!("{method:?}");
+            group.bench_with_input(BenchmarkId::new(format!("new/{id}"), name), &s, |b, s| {
+                b.iter(|| ReconciliationMatrix::new(black_box(s), method).unwrap())
+            });
+
+            let g = ReconciliationMatrix::new(&s, method).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new(format!("reconcile/{id}"), name),
+                &y_hat,
+                |b, y_hat| b.iter(|| g.reconcile(black_box(y_hat)).unwrap()),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_aggregate_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aggregate_all");
+    group.sample_size(10);
+
+    for &(name, levels, groups) in SIZES {
+        let hts = random_hierarchy(levels, groups, PERIODS, SEED);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &hts, |b, hts| {
+            b.iter(|| hts.aggregate_all().unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_tree,
+    bench_summation_matrix,
+    bench_reconciliation,
+    bench_aggregate_all
+);
+criterion_main!(benches);
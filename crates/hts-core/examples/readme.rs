@@ -17,6 +17,7 @@
 
 //! Example for hts-core crate showcasing basic usage for the README.
 
+#[allow(clippy::useless_vec)]
 fn main() {
     use hts_core::{HierarchicalTimeSeries, HierarchySpec};
     use polars::prelude::*;
@@ -0,0 +1,326 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Construction options for hierarchical time series.
+//!
+//! This module defines `HtsConfig`, which controls how a
+//! `HierarchicalTimeSeries` is built from raw bottom-level data.
+
+use polars::prelude::*;
+use std::cmp::Ordering;
+
+/// What to do with rows whose time value is null or blank.
+///
+/// Real-world CSV exports often end with blank lines or contain rows with an
+/// empty time cell. These cannot be parsed into a [`Period`](crate::Period).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NaPeriodPolicy {
+    /// Fail construction, reporting the first offending row.
+    #[default]
+    Error,
+
+    /// Remove the offending rows from the bottom-level data before parsing.
+    ///
+    /// This is usually the right choice for messy spreadsheet exports.
+    Drop,
+
+    /// Keep the offending rows in the bottom-level data, but do not treat the
+    /// blank value as a time period.
+    ///
+    /// Their time value is stored as null. The rows still contribute series
+    /// to the hierarchy, but belong to no period, so aggregation leaves them
+    /// out.
+    Keep,
+}
+
+/// How values in the time column are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PeriodFormat {
+    /// Calendar periods, auto-detected by [`Period::parse`](crate::Period::parse).
+    #[default]
+    Calendar,
+
+    /// Plain integer indices (1, 2, 3, ...) with no calendar meaning, parsed
+    /// as [`Period::Ordinal`](crate::Period::Ordinal).
+    ///
+    /// Useful for simulation data and relative-time experiments.
+    Ordinal,
+}
+
+/// What the values of the value column measure, which decides whether they
+/// may be summed up the hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueKind {
+    /// Quantities that add up, such as sales or trips.
+    #[default]
+    Additive,
+
+    /// Rates, shares or percentages, e.g. an unemployment rate.
+    Ratio,
+
+    /// Index numbers relative to a base, e.g. a price index.
+    Index,
+}
+
+impl ValueKind {
+    /// Returns true if values of this kind may be summed.
+    pub fn is_additive(self) -> bool {
+        self == Self::Additive
+    }
+}
+
+/// How raw rows sharing a bottom-level key and period are collapsed into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AggregationFn {
+    /// Sum of the values, what aggregation does to such rows anyway.
+    #[default]
+    Sum,
+
+    /// Arithmetic mean of the values.
+    Mean,
+
+    /// Smallest value.
+    Min,
+
+    /// Largest value.
+    Max,
+
+    /// Value of the first row, in data order.
+    First,
+
+    /// Value of the last row, in data order.
+    Last,
+}
+
+impl AggregationFn {
+    /// Returns the Polars expression reducing `column`, which is null for a
+    /// group without any non-null value (except for `First` and `Last`,
+    /// which return the value at their row as is).
+    pub(crate) fn expr(self, column: &str) -> Expr {
+        let values = col(column);
+        match self {
+            Self::Sum => when(values.clone().count().eq(lit(0)))
+                .then(lit(NULL).cast(DataType::Float64))
+                .otherwise(values.sum()),
+            Self::Mean => values.mean(),
+            Self::Min => values.min(),
+            Self::Max => values.max(),
+            Self::First => values.first(),
+            Self::Last => values.last(),
+        }
+    }
+}
+
+/// Order of the labels of a text grouping column.
+///
+/// The collation sorts the bottom-level series, which fixes the columns of
+/// the summation matrix, and the nodes within each level. Grouping columns
+/// of other types keep their natural order: numbers by value, dates
+/// chronologically.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::Collation;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(Collation::CaseSensitive.compare("10", "2"), Ordering::Less);
+/// assert_eq!(Collation::Numeric.compare("10", "2"), Ordering::Greater);
+/// assert_eq!(Collation::CaseInsensitive.compare("b", "C"), Ordering::Less);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collation {
+    /// Byte order of the UTF-8 labels (the default), so `"B" < "a"` and
+    /// `"10" < "2"`.
+    #[default]
+    CaseSensitive,
+    /// Order ignoring case, so `"a" < "B"`; labels equal but for case are
+    /// ordered by bytes.
+    CaseInsensitive,
+    /// Labels that parse as numbers first, by value, so `"2" < "10"`; the
+    /// other labels follow in byte order. Ties, such as `"1"` and `"01"`, are
+    /// ordered by bytes.
+    Numeric,
+}
+
+impl Collation {
+    /// Compares two labels under this collation.
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::CaseSensitive => a.cmp(b),
+            Self::CaseInsensitive => a
+                .chars()
+                .flat_map(char::to_lowercase)
+                .cmp(b.chars().flat_map(char::to_lowercase))
+                .then_with(|| a.cmp(b)),
+            Self::Numeric => {
+                let number = |s: &str| s.trim().parse::<f64>().ok().filter(|x| !x.is_nan());
+                match (number(a), number(b)) {
+                    (Some(x), Some(y)) => x.total_cmp(&y),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+                .then_with(|| a.cmp(b))
+            }
+        }
+    }
+}
+
+/// Options controlling how CSV files are read.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::{CsvImportOptions, HtsConfig};
+///
+/// let config = HtsConfig {
+///     csv: CsvImportOptions { memory_map: false },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvImportOptions {
+    /// Whether to memory-map the file instead of reading it into memory (the
+    /// default).
+    ///
+    /// Mapping lets repeated loads of a large, unchanging file share the page
+    /// cache instead of copying it. If the file cannot be mapped, e.g. on
+    /// some network filesystems, it is read into memory instead.
+    pub memory_map: bool,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self { memory_map: true }
+    }
+}
+
+/// Options controlling how a `HierarchicalTimeSeries` is built.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::{HtsConfig, NaPeriodPolicy};
+///
+/// let config = HtsConfig {
+///     na_period: NaPeriodPolicy::Drop,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtsConfig {
+    /// Handling of null or blank values in the time column.
+    pub na_period: NaPeriodPolicy,
+
+    /// Interpretation of values in the time column.
+    pub period_format: PeriodFormat,
+
+    /// Whether to sort the distinct periods chronologically (the default).
+    ///
+    /// When `false`, `HierarchicalTimeSeries::periods()` lists periods in the
+    /// order they first appear in the data.
+    pub sort_periods: bool,
+
+    /// Whether the group-bys of the aggregation levels run concurrently (the
+    /// default).
+    pub parallel: bool,
+
+    /// Number of threads of the dataset's own thread pool, or `None` (the
+    /// default) to run on the caller's.
+    ///
+    /// With `Some`, aggregation queries are collected and reconciliation is
+    /// solved inside a pool of that many threads, which bounds `faer`'s
+    /// linear algebra. `Some(1)` also evaluates the aggregation levels one at
+    /// a time, whatever [`Self::parallel`] says. `Some(0)` picks a size from
+    /// the available parallelism. Polars' own kernels always dispatch to
+    /// Polars' process-wide pool; see the crate documentation on
+    /// parallelism.
+    pub n_threads: Option<usize>,
+
+    /// Whether aggregation must refuse to zero-fill missing observations.
+    ///
+    /// When `true`, `aggregate_all()` and its variants fail if any (bottom
+    /// series, period) cell has no non-null observation, instead of treating
+    /// it as zero. `HierarchicalTimeSeries::missing_cells()` lists the cells
+    /// to fill.
+    pub strict: bool,
+
+    /// Text tokens that mark a missing value in the time and value columns,
+    /// e.g. `"NA"` or `"."` in R and spreadsheet exports.
+    ///
+    /// Matching cells (ignoring surrounding whitespace) become null before
+    /// [`Self::na_period`] applies, and a text value column is then parsed as
+    /// numbers. Empty by default, leaving Polars' standard null handling.
+    pub null_values: Vec<String>,
+
+    /// How to collapse raw rows sharing a bottom-level key and period, when
+    /// the data is finer-grained than the bottom level.
+    ///
+    /// With `Some`, the bottom-level data is reduced to one row per (bottom
+    /// key, period) at construction, keeping only the grouping, time, value
+    /// and [weight](Self::weight_col) columns; any other column is dropped.
+    /// Weights are summed. Non-additive values with a weight column are
+    /// collapsed to their weighted mean whatever the function, so that
+    /// aggregation gives the same weighted means as on the raw rows.
+    /// `None` (the default) keeps the rows as given; aggregation then sums
+    /// them, as [`AggregationFn::Sum`] would.
+    pub pre_aggregate: Option<AggregationFn>,
+
+    /// What the value column measures.
+    ///
+    /// Non-additive values ([`ValueKind::Ratio`], [`ValueKind::Index`]) are
+    /// never summed: aggregation averages them weighted by
+    /// [`Self::weight_col`], and fails without one.
+    pub value_semantics: ValueKind,
+
+    /// Column weighting non-additive values in aggregation, e.g. the labor
+    /// force behind an unemployment rate. Ignored for additive values.
+    ///
+    /// Weights are read row by row, so they may change from period to period
+    /// (population-weighted per-capita measures, Divisia-style price
+    /// indices): each aggregate is the mean of its bottom-level values in
+    /// that period, weighted by their weights in that period.
+    pub weight_col: Option<String>,
+
+    /// How `HierarchicalTimeSeries::from_csv_with_config()` reads the file.
+    pub csv: CsvImportOptions,
+
+    /// Order of the labels of text grouping columns, which fixes the order
+    /// of the bottom-level series and of the nodes in each level. Byte order
+    /// by default.
+    pub collation: Collation,
+}
+
+impl Default for HtsConfig {
+    fn default() -> Self {
+        Self {
+            na_period: NaPeriodPolicy::default(),
+            period_format: PeriodFormat::default(),
+            sort_periods: true,
+            parallel: true,
+            n_threads: None,
+            strict: false,
+            null_values: Vec::new(),
+            pre_aggregate: None,
+            value_semantics: ValueKind::default(),
+            weight_col: None,
+            csv: CsvImportOptions::default(),
+            collation: Collation::default(),
+        }
+    }
+}
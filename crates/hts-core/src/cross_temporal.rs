@@ -0,0 +1,160 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Cross-temporal hierarchies.
+//!
+//! A cross-temporal structure combines the cross-sectional hierarchy, with
+//! summation matrix $\mathbf{S}_{cs}$ (n × m), and a temporal hierarchy over
+//! one cycle of p high-frequency periods, with summation matrix
+//! $\mathbf{S}_{te}$ (k* × p, see [`SummationMatrix::temporal`]).
+//!
+//! Arranging the bottom-level series over one cycle as the m × p matrix
+//! $\mathbf{B}$, every series at every temporal aggregation order is
+//! $\mathbf{Y} = \mathbf{S}_{cs}\mathbf{B}\mathbf{S}_{te}'$ (n × k*).
+//! Vectorized row by row, this is the Kronecker-structured operator
+//! $\text{vec}(\mathbf{Y}') = (\mathbf{S}_{cs} \otimes \mathbf{S}_{te})\,\text{vec}(\mathbf{B}')$,
+//! an (n·k*) × (m·p) matrix.
+
+use crate::error::Result;
+use crate::summation_matrix::SummationMatrix;
+use faer::{Mat, MatRef};
+
+/// Cross-sectional and temporal summation matrices of a cross-temporal
+/// hierarchy.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::CrossTemporalHts;
+/// use hts_core::testing::random_hierarchy;
+///
+/// // 2 states × 2 regions, quarterly data
+/// let hts = random_hierarchy(&[2, 2], &[], 4, 1);
+/// let ct = CrossTemporalHts::new(hts.summation_matrix().clone(), 4).unwrap();
+///
+/// // (7 series × 7 temporal aggregates) × (4 bottom series × 4 quarters)
+/// assert_eq!(ct.shape(), (49, 16));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrossTemporalHts {
+    /// Cross-sectional summation matrix (n × m).
+    cross: SummationMatrix,
+
+    /// Temporal summation matrix (k* × p).
+    temporal: SummationMatrix,
+}
+
+impl CrossTemporalHts {
+    /// Combines a cross-sectional summation matrix with the temporal
+    /// hierarchy of `period` high-frequency periods per cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `period` is zero.
+    pub fn new(cross: SummationMatrix, period: usize) -> Result<Self> {
+        Ok(Self {
+            cross,
+            temporal: SummationMatrix::temporal(period)?,
+        })
+    }
+
+    /// Returns the cross-sectional summation matrix (n × m).
+    pub fn cross_sectional(&self) -> &SummationMatrix {
+        &self.cross
+    }
+
+    /// Returns the temporal summation matrix (k* × p).
+    pub fn temporal(&self) -> &SummationMatrix {
+        &self.temporal
+    }
+
+    /// Returns the shape of the combined operator, (n·k*, m·p).
+    pub fn shape(&self) -> (usize, usize) {
+        let (n, m) = self.cross.shape();
+        let (k, p) = self.temporal.shape();
+        (n * k, m * p)
+    }
+
+    /// Builds the combined summation operator $\mathbf{S}_{cs} \otimes \mathbf{S}_{te}$.
+    ///
+    /// Row `i * k* + h` holds cross-sectional series i at temporal aggregate
+    /// h; column `j * p + t` holds bottom-level series j at period t. The
+    /// operator is dense, so prefer [`Self::aggregate`] for large structures.
+    pub fn summation_operator(&self) -> Mat<f64> {
+        let (n, m) = self.shape();
+        let (k, p) = self.temporal.shape();
+        let cs = &self.cross.matrix;
+        let te = &self.temporal.matrix;
+
+        Mat::from_fn(n, m, |row, col| {
+            cs[(row / k, col / p)] * te[(row % k, col % p)]
+        })
+    }
+
+    /// Aggregates bottom-level series over one cycle to every cross-sectional
+    /// series at every temporal order: Y = S_cs B S_te'.
+    ///
+    /// `bottom` is the m × p matrix B; the result is n × k*.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bottom` is not m × p.
+    pub fn aggregate(&self, bottom: MatRef<'_, f64>) -> Mat<f64> {
+        let expected = (self.cross.n_bottom(), self.temporal.n_bottom());
+        assert_eq!(
+            (bottom.nrows(), bottom.ncols()),
+            expected,
+            "Expected a {} × {} bottom matrix",
+            expected.0,
+            expected.1
+        );
+
+        &self.cross.matrix * bottom * self.temporal.matrix.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::random_hierarchy;
+
+    #[test]
+    fn test_operator_matches_aggregate() {
+        let hts = random_hierarchy(&[2], &[2], 2, 1);
+        let ct = CrossTemporalHts::new(hts.summation_matrix().clone(), 4).unwrap();
+        let (n, m) = ct.cross_sectional().shape();
+        let (k, p) = ct.temporal().shape();
+        assert_eq!(ct.shape(), (n * k, m * p));
+
+        let bottom = Mat::from_fn(m, p, |j, t| (j * p + t) as f64);
+        let y = ct.aggregate(bottom.as_ref());
+        assert_eq!((y.nrows(), y.ncols()), (n, k));
+
+        // vec(B') stacks each bottom series' periods
+        let b = Mat::from_fn(m * p, 1, |r, _| bottom[(r / p, r % p)]);
+        let y_vec = ct.summation_operator() * &b;
+        for i in 0..n {
+            for h in 0..k {
+                assert_eq!(y_vec[(i * k + h, 0)], y[(i, h)]);
+            }
+        }
+
+        // Grand total over the whole cycle
+        let total: f64 = (0..m * p).map(|r| r as f64).sum();
+        assert_eq!(y[(0, 0)], total);
+    }
+}
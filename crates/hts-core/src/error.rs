@@ -41,6 +41,28 @@ pub enum HtsError {
     /// Column not found in DataFrame.
     #[error("Column not found: {0}")]
     ColumnNotFound(String),
+
+    /// Inputs that should share a schema do not.
+    #[error("Schema mismatch: {0}")]
+    SchemaMismatch(String),
+
+    /// Reconciliation failed for a numerical or dimensional reason, such as
+    /// forecasts or weights of the wrong length.
+    #[error("Reconciliation error: {0}")]
+    Reconciliation(String),
+
+    /// Observations required in strict mode are missing.
+    #[error("Missing data: {0}")]
+    MissingData(String),
+
+    /// Non-additive values (rates, indices) were about to be summed.
+    #[error("Non-additive values: {0}")]
+    NonAdditive(String),
+
+    /// A matrix that must be factorized or inverted is singular (or not
+    /// positive definite).
+    #[error("Singular matrix: {0}")]
+    SingularMatrix(String),
 }
 
 /// Result type alias for HTS-Core operations.
@@ -20,6 +20,7 @@
 //! This module defines how hierarchical and grouped time series structures
 //! are specified and represented internally.
 
+use crate::config::Collation;
 use crate::error::{HtsError, Result};
 use polars::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -44,10 +45,11 @@ use std::collections::{HashMap, HashSet};
 /// let spec = HierarchySpec {
 ///     hierarchy: vec!["State".into(), "Region".into()],
 ///     groups: vec!["Purpose".into()],
+///     ..Default::default()
 /// };
 /// ```
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct HierarchySpec {
     /// Columns with strict parent-child nesting, ordered from top to bottom.
     /// Each value at level i belongs to exactly one value at level i-1.
@@ -55,43 +57,129 @@ pub struct HierarchySpec {
 
     /// Columns that cross with the hierarchy at all levels.
     /// These create additional aggregation dimensions.
+    ///
+    /// Their order is not significant: groups are always used sorted by
+    /// name, so specs listing the same groups in any order build identical
+    /// trees, series ids and levels, and compare equal.
     pub groups: Vec<String>,
+
+    /// Id of the root node aggregating every series. Defaults to
+    /// [`DEFAULT_TOTAL_LABEL`].
+    pub total_label: String,
+}
+
+/// Default id of the root node.
+pub const DEFAULT_TOTAL_LABEL: &str = "Total";
+
+/// Shape of a [`HierarchySpec`], as returned by [`HierarchySpec::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureKind {
+    /// Hierarchy columns only (or no columns at all, just the total).
+    Hierarchical,
+    /// Group columns only.
+    Grouped,
+    /// Both hierarchy and group columns.
+    Mixed,
+}
+
+impl PartialEq for HierarchySpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.hierarchy == other.hierarchy
+            && self.sorted_groups() == other.sorted_groups()
+            && self.total_label == other.total_label
+    }
+}
+
+impl Eq for HierarchySpec {}
+
+impl std::hash::Hash for HierarchySpec {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hierarchy.hash(state);
+        self.sorted_groups().hash(state);
+        self.total_label.hash(state);
+    }
+}
+
+impl Default for HierarchySpec {
+    fn default() -> Self {
+        Self {
+            hierarchy: Vec::new(),
+            groups: Vec::new(),
+            total_label: DEFAULT_TOTAL_LABEL.to_string(),
+        }
+    }
 }
 
 impl HierarchySpec {
     /// Creates a new `HierarchySpec`.
     pub fn new(hierarchy: Vec<String>, groups: Vec<String>) -> Self {
-        Self { hierarchy, groups }
+        Self {
+            hierarchy,
+            groups,
+            ..Default::default()
+        }
     }
 
     /// Creates a spec with only hierarchical columns (no grouping).
     pub fn hierarchical(columns: Vec<String>) -> Self {
         Self {
             hierarchy: columns,
-            groups: Vec::new(),
+            ..Default::default()
         }
     }
 
     /// Creates a spec with only grouped columns (no hierarchy).
     pub fn grouped(columns: Vec<String>) -> Self {
         Self {
-            hierarchy: Vec::new(),
             groups: columns,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the id of the root node (e.g., `"Brasil"` instead of `"Total"`).
+    ///
+    /// Useful for localized output, or when the data has a category that is
+    /// literally called "Total".
+    pub fn with_total_label(mut self, label: impl Into<String>) -> Self {
+        self.total_label = label.into();
+        self
+    }
+
+    /// Classifies the structure as purely hierarchical, purely grouped, or
+    /// mixed.
+    ///
+    /// Reconciliation defaults such as top-down behavior depend on it. A spec
+    /// without columns is reported as [`StructureKind::Hierarchical`].
+    pub fn kind(&self) -> StructureKind {
+        match (self.hierarchy.is_empty(), self.groups.is_empty()) {
+            (_, true) => StructureKind::Hierarchical,
+            (true, false) => StructureKind::Grouped,
+            (false, false) => StructureKind::Mixed,
         }
     }
 
-    /// Returns all columns involved in the structure.
+    /// Returns all columns involved in the structure: the hierarchy columns
+    /// from the top down, then the group columns sorted by name.
     pub fn all_columns(&self) -> Vec<&str> {
         self.hierarchy
             .iter()
-            .chain(self.groups.iter())
             .map(String::as_str)
+            .chain(self.sorted_groups())
             .collect()
     }
 
+    /// Returns the group columns in canonical (sorted) order.
+    fn sorted_groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = self.groups.iter().map(String::as_str).collect();
+        groups.sort_unstable();
+        groups
+    }
+
     /// Returns all combinations of columns that define aggregation levels.
     ///
     /// Includes the root (empty), hierarchical levels, and crossed levels with groups.
+    /// Columns within each level are in canonical order: hierarchy columns
+    /// first, in hierarchy order, then group columns, sorted by name.
     pub fn level_combinations(&self) -> Vec<Vec<String>> {
         let n_hier = self.hierarchy.len();
         let has_groups = !self.groups.is_empty();
@@ -143,31 +231,172 @@ impl HierarchySpec {
         });
         all_level_keys.sort_by_key(|k| k.len());
 
+        // Canonical column order within each level
+        let position: HashMap<&str, usize> = self
+            .all_columns()
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (c, i))
+            .collect();
+        for level in &mut all_level_keys {
+            level.sort_by_key(|c| position[c.as_str()]);
+        }
+
         all_level_keys
     }
 
+    /// Composes the id of the series identified by `labels`.
+    ///
+    /// Labels are joined with `/` in canonical order: hierarchy columns first,
+    /// in hierarchy order, then group columns, sorted by name. Columns absent
+    /// from `labels` are skipped, and an empty map yields `total_label`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::HierarchySpec;
+    /// use std::collections::HashMap;
+    ///
+    /// let spec = HierarchySpec::new(vec!["State".into(), "Region".into()], vec!["Purpose".into()]);
+    /// let labels = HashMap::from([
+    ///     ("Purpose".to_string(), "Business".to_string()),
+    ///     ("State".to_string(), "South Australia".to_string()),
+    /// ]);
+    ///
+    /// assert_eq!(spec.series_id(&labels), "South Australia/Business");
+    /// ```
+    pub fn series_id(&self, labels: &HashMap<String, String>) -> String {
+        if labels.is_empty() {
+            return self.total_label.clone();
+        }
+
+        self.all_columns()
+            .into_iter()
+            .filter_map(|c| labels.get(c).map(String::as_str))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
     /// Validates that all specified columns exist in the DataFrame.
+    ///
+    /// See [`Self::validate_schema`].
     pub fn validate(&self, df: &DataFrame) -> Result<()> {
-        let df_cols: HashSet<String> = df
-            .get_column_names()
-            .into_iter()
-            .map(|s| s.to_string())
-            .collect();
+        self.validate_schema(df.schema())
+    }
+
+    /// Validates that all specified columns exist in a schema.
+    ///
+    /// Useful for lazy scans, where the schema is known before the data is read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Hierarchy`] if a column is listed more than once,
+    /// whether within `hierarchy`, within `groups`, or in both, and
+    /// [`HtsError::ColumnNotFound`] if a column is missing from the schema.
+    pub fn validate_schema(&self, schema: &Schema) -> Result<()> {
+        let mut seen = HashSet::new();
+        for col in self.all_columns() {
+            if !seen.insert(col) {
+                return Err(HtsError::Hierarchy(format!(
+                    "Column '{col}' is listed more than once in the hierarchy specification"
+                )));
+            }
+        }
 
         for col in self.all_columns() {
-            if !df_cols.contains(col) {
+            if !schema.contains(col) {
                 return Err(HtsError::ColumnNotFound(col.to_string()));
             }
         }
 
         Ok(())
     }
+
+    /// Returns every series id that more than one label combination of `df`
+    /// produces, including labels that join to the total label, in level
+    /// order.
+    pub(crate) fn clashing_ids(&self, df: &DataFrame) -> Result<Vec<String>> {
+        let mut seen = HashSet::from([self.total_label.clone()]);
+        let mut clashes = Vec::new();
+        for level_cols in self.level_combinations() {
+            if level_cols.is_empty() {
+                continue;
+            }
+            let unique = df
+                .clone()
+                .lazy()
+                .select(
+                    level_cols
+                        .iter()
+                        .map(|c| col(c.as_str()))
+                        .collect::<Vec<_>>(),
+                )
+                .unique(None, UniqueKeepStrategy::First)
+                .collect()?;
+            let labels = level_cols
+                .iter()
+                .map(|name| column_labels(&unique, name))
+                .collect::<Result<Vec<_>>>()?;
+            for row in 0..unique.height() {
+                let key: HashMap<String, String> = level_cols
+                    .iter()
+                    .zip(&labels)
+                    .map(|(name, values)| (name.clone(), values[row].clone()))
+                    .collect();
+                let id = self.series_id(&key);
+                if !seen.insert(id.clone()) && !clashes.contains(&id) {
+                    clashes.push(id);
+                }
+            }
+        }
+        Ok(clashes)
+    }
+
+    /// Checks that hierarchy columns are listed from the top down.
+    ///
+    /// Each hierarchy column must have at least as many distinct values as
+    /// the one before it, which is necessary (but not sufficient) for every
+    /// column to nest within its predecessor. Listing a child before its
+    /// parent, e.g. `["Region", "State"]`, usually fails this check.
+    ///
+    /// This is a heuristic and is not run during construction: a lower level
+    /// whose labels repeat across parents (say, "North" and "South" within
+    /// every state) can legitimately have fewer distinct values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first offending pair of columns, or if a
+    /// hierarchy column is missing.
+    pub fn check_order(&self, df: &DataFrame) -> Result<()> {
+        let mut previous: Option<(&str, usize)> = None;
+
+        for name in &self.hierarchy {
+            let n_unique = df
+                .column(name)
+                .map_err(|_| HtsError::ColumnNotFound(name.clone()))?
+                .n_unique()?;
+
+            if let Some((parent, parent_unique)) = previous
+                && n_unique < parent_unique
+            {
+                return Err(HtsError::Hierarchy(format!(
+                    "Column '{name}' has fewer distinct values ({n_unique}) than its parent \
+                     '{parent}' ({parent_unique}); hierarchy columns should be listed from \
+                     the top down"
+                )));
+            }
+            previous = Some((name, n_unique));
+        }
+
+        Ok(())
+    }
 }
 
 /// A node representing one series in the hierarchy.
 ///
 /// Each node corresponds to a single time series at some level of aggregation.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     /// Unique identifier for this node (e.g., "South Australia/Adelaide/Business").
     pub id: String,
@@ -180,6 +409,20 @@ pub struct Node {
 
     /// Labels for the grouping keys at this node.
     pub labels: HashMap<String, String>,
+
+    /// Grouping columns of this node's level, in canonical order (hierarchy
+    /// columns first, then group columns). Empty for the total.
+    pub columns: Vec<String>,
+
+    /// Arbitrary attributes attached for reporting (e.g., population or
+    /// manager), kept apart from the structural `labels`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub meta: HashMap<String, String>,
+
+    /// Whether the node is at the most disaggregated level, set when the
+    /// tree is built. Required when deserializing, so a tree cannot lose its
+    /// bottom level.
+    bottom: bool,
 }
 
 impl Node {
@@ -190,12 +433,57 @@ impl Node {
             level,
             aggregates_from: Vec::new(),
             labels: HashMap::new(),
+            columns: Vec::new(),
+            meta: HashMap::new(),
+            bottom: false,
         }
     }
 
+    /// Returns the node's labels as ordered `(column, value)` pairs.
+    ///
+    /// Pairs follow [`Self::columns`], so the path reads from the top of the
+    /// hierarchy down, followed by group labels. The total has an empty path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::polars::prelude::*;
+    /// use hts_core::{HierarchySpec, HierarchyTree};
+    ///
+    /// let df = df! {
+    ///     "State" => ["SP", "SP"],
+    ///     "City" => ["Campinas", "Santos"],
+    /// }
+    /// .unwrap();
+    ///
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into(), "City".into()]);
+    /// let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+    /// let node = tree.get_node("SP/Santos").unwrap();
+    ///
+    /// assert_eq!(node.path(), vec![("State", "SP"), ("City", "Santos")]);
+    /// ```
+    pub fn path(&self) -> Vec<(&str, &str)> {
+        self.columns
+            .iter()
+            .filter_map(|c| self.labels.get(c).map(|value| (c.as_str(), value.as_str())))
+            .collect()
+    }
+
+    /// Returns true if this is the root node aggregating every series.
+    ///
+    /// The root is identified structurally (level 0, no labels) rather than by
+    /// its id, which is configurable through `HierarchySpec::total_label`.
+    pub fn is_total(&self) -> bool {
+        self.level == 0 && self.labels.is_empty()
+    }
+
     /// Returns true if this is a bottom-level (most disaggregated) node.
+    ///
+    /// Bottom-ness comes from the node's level in its tree, not from how many
+    /// series it aggregates: a total over a single series aggregates one
+    /// series too. Nodes created with [`Self::new`] are not bottom nodes.
     pub fn is_bottom(&self) -> bool {
-        self.aggregates_from.len() == 1
+        self.bottom
     }
 }
 
@@ -205,6 +493,7 @@ impl Node {
 /// the bottom (most disaggregated) level, along with the aggregation
 /// relationships needed to build the summation matrix.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HierarchyTree {
     /// All nodes in the tree, ordered by level then by ID.
     nodes: Vec<Node>,
@@ -217,6 +506,9 @@ pub struct HierarchyTree {
 
     /// Map from node ID to index in `nodes`.
     id_to_index: HashMap<String, usize>,
+
+    /// Distinct bottom-level key combinations, one row per S column.
+    bottom_keys: DataFrame,
 }
 
 impl HierarchyTree {
@@ -231,20 +523,175 @@ impl HierarchyTree {
     ///
     /// A `HierarchyTree` with all aggregation levels computed.
     pub fn from_dataframe(df: &DataFrame, spec: &HierarchySpec) -> Result<Self> {
+        Self::from_dataframe_with_collation(df, spec, Collation::default())
+    }
+
+    /// Builds a hierarchy tree from a DataFrame, ordering the labels of text
+    /// columns by `collation`.
+    ///
+    /// [`Self::from_dataframe`] uses the default byte order. See
+    /// [`HtsConfig::collation`](crate::HtsConfig::collation).
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::from_lazy`].
+    pub fn from_dataframe_with_collation(
+        df: &DataFrame,
+        spec: &HierarchySpec,
+        collation: Collation,
+    ) -> Result<Self> {
         spec.validate(df)?;
+        Self::from_lazy_with_collation(df.clone().lazy(), spec, collation)
+    }
 
-        // Get unique combinations of all grouping columns (bottom level)
-        let all_cols = spec.all_columns();
-        let bottom_df = df
+    /// Builds a hierarchy tree from bottom-level data identified by a single
+    /// code column and a separate mapping table holding its ancestors.
+    ///
+    /// `mapping_df` has one row per code, with `key_col` and the ancestor
+    /// columns `parent_cols`, listed from the top down (e.g., `["State",
+    /// "Region"]` for codes within regions within states). The mapping is
+    /// joined onto `bottom_df` (see [`Self::join_mapping`]) and the tree is
+    /// built for the hierarchy `parent_cols` followed by `key_col`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::join_mapping`] and
+    /// [`Self::from_dataframe`].
+    pub fn from_mapping(
+        bottom_df: &DataFrame,
+        mapping_df: &DataFrame,
+        key_col: &str,
+        parent_cols: &[&str],
+    ) -> Result<Self> {
+        let joined = Self::join_mapping(bottom_df, mapping_df, key_col, parent_cols)?;
+        let hierarchy = parent_cols
+            .iter()
+            .chain([&key_col])
+            .map(|c| c.to_string())
+            .collect();
+        Self::from_dataframe(&joined, &HierarchySpec::hierarchical(hierarchy))
+    }
+
+    /// Adds the ancestor columns `parent_cols` of `mapping_df` to
+    /// `bottom_df`, matching rows on `key_col`.
+    ///
+    /// The result is the denormalized frame [`Self::from_mapping`] builds
+    /// from; pass it to `HierarchicalTimeSeries::new` with the same
+    /// hierarchy to work with the data. Rows keep their order, and the
+    /// mapping's key is cast to the type of the bottom-level key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::ColumnNotFound`] if a column is missing, and
+    /// [`HtsError::Hierarchy`] if `bottom_df` already has a parent column, a
+    /// code appears more than once in the mapping, or a bottom-level code
+    /// has no mapping.
+    pub fn join_mapping(
+        bottom_df: &DataFrame,
+        mapping_df: &DataFrame,
+        key_col: &str,
+        parent_cols: &[&str],
+    ) -> Result<DataFrame> {
+        let key_dtype = bottom_df
+            .column(key_col)
+            .map_err(|_| HtsError::ColumnNotFound(key_col.to_string()))?
+            .dtype()
+            .clone();
+        for &name in parent_cols.iter().chain([&key_col]) {
+            if mapping_df.column(name).is_err() {
+                return Err(HtsError::ColumnNotFound(format!("{name} (in the mapping)")));
+            }
+        }
+        if let Some(name) = parent_cols.iter().find(|&&c| bottom_df.column(c).is_ok()) {
+            return Err(HtsError::Hierarchy(format!(
+                "Column '{name}' is in both the bottom-level data and the mapping"
+            )));
+        }
+
+        let mut mapping_cols = vec![col(key_col).cast(key_dtype)];
+        mapping_cols.extend(parent_cols.iter().map(|&c| col(c)));
+        let mapping = mapping_df.clone().lazy().select(mapping_cols).collect()?;
+        let duplicated = mapping.select([key_col])?.is_duplicated()?;
+        if let Some(row) = duplicated.iter().position(|d| d == Some(true)) {
+            return Err(HtsError::Hierarchy(format!(
+                "Code {} appears more than once in the mapping",
+                mapping.column(key_col)?.get(row)?
+            )));
+        }
+
+        let joined = bottom_df
             .clone()
             .lazy()
+            .join(
+                mapping.lazy().with_column(lit(true).alias("__mapped")),
+                [col(key_col)],
+                [col(key_col)],
+                JoinArgs::new(JoinType::Left),
+            )
+            .collect()?;
+
+        let mapped = joined.column("__mapped")?.bool()?;
+        if let Some(row) = mapped.iter().position(|m| m.is_none()) {
+            return Err(HtsError::Hierarchy(format!(
+                "Code {} at row {row} has no entry in the mapping",
+                joined.column(key_col)?.get(row)?
+            )));
+        }
+        Ok(joined.drop("__mapped")?)
+    }
+
+    /// Builds a hierarchy tree from a lazy scan of the bottom-level data.
+    ///
+    /// Only the distinct combinations of the spec's columns are materialized,
+    /// using Polars' streaming engine, so the scan itself may be larger than
+    /// memory (e.g., from `LazyCsvReader`).
+    ///
+    /// Construction is iterative and linear in the number of bottom series
+    /// per level, so deep hierarchies are safe to build.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a spec column is missing from the scan or the scan
+    /// fails, and [`HtsError::Hierarchy`] if two distinct label combinations
+    /// join to the same series id (e.g., `"A/B"` + `"C"` and `"A"` + `"B/C"`),
+    /// or a label equals the total label.
+    pub fn from_lazy(scan: LazyFrame, spec: &HierarchySpec) -> Result<Self> {
+        Self::from_lazy_with_collation(scan, spec, Collation::default())
+    }
+
+    /// Builds a hierarchy tree from a lazy scan, ordering the labels of text
+    /// columns by `collation`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::from_lazy`].
+    pub fn from_lazy_with_collation(
+        scan: LazyFrame,
+        spec: &HierarchySpec,
+        collation: Collation,
+    ) -> Result<Self> {
+        let mut scan = scan;
+        let schema = scan.collect_schema()?;
+        spec.validate_schema(&schema)?;
+
+        // Get unique combinations of all grouping columns (bottom level)
+        let all_cols = spec.all_columns();
+        let bottom_df = scan
             .select(all_cols.iter().map(|c| col(*c)).collect::<Vec<_>>())
             .unique(None, UniqueKeepStrategy::First)
             .sort(all_cols.clone(), SortMultipleOptions::default())
+            .with_streaming(true)
             .collect()?;
+        let bottom_df = sort_labels(bottom_df, &all_cols, collation)?;
 
         let n_bottom = bottom_df.height();
 
+        // Labels of every bottom row, read once per column
+        let bottom_labels: HashMap<&str, Vec<String>> = all_cols
+            .iter()
+            .map(|&name| Ok((name, column_labels(&bottom_df, name)?)))
+            .collect::<Result<_>>()?;
+
         // Build bottom-level nodes first
         let mut nodes = Vec::new();
         let mut id_to_index = HashMap::new();
@@ -258,8 +705,9 @@ impl HierarchyTree {
         for (level, level_cols) in all_level_keys.iter().enumerate() {
             if level_cols.is_empty() {
                 // Total node
-                let mut node = Node::new("Total".to_string(), level);
+                let mut node = Node::new(spec.total_label.clone(), level);
                 node.aggregates_from = (0..n_bottom).collect();
+                node.bottom = level + 1 == n_levels;
                 id_to_index.insert(node.id.clone(), nodes.len());
                 nodes.push(node);
             } else {
@@ -282,40 +730,59 @@ impl HierarchyTree {
                         SortMultipleOptions::default(),
                     )
                     .collect()?;
+                let names: Vec<&str> = level_cols.iter().map(String::as_str).collect();
+                let unique_df = sort_labels(unique_df, &names, collation)?;
+
+                let unique_labels = level_cols
+                    .iter()
+                    .map(|name| column_labels(&unique_df, name))
+                    .collect::<Result<Vec<_>>>()?;
 
+                // Node index of each label combination at this level
+                let mut key_to_index = HashMap::new();
                 for row_idx in 0..unique_df.height() {
-                    let mut labels = HashMap::new();
-                    let mut id_parts = Vec::new();
-
-                    for col_name in level_cols {
-                        let series = unique_df.column(col_name)?;
-                        let value = series.get(row_idx)?.to_string();
-                        let value = value.trim_matches('"').to_string();
-                        labels.insert(col_name.clone(), value.clone());
-                        id_parts.push(value);
-                    }
+                    let key: Vec<&str> = unique_labels
+                        .iter()
+                        .map(|labels| labels[row_idx].as_str())
+                        .collect();
+                    let labels: HashMap<String, String> = level_cols
+                        .iter()
+                        .zip(&key)
+                        .map(|(name, value)| (name.clone(), value.to_string()))
+                        .collect();
 
-                    let id = id_parts.join("/");
+                    let id = spec.series_id(&labels);
                     let mut node = Node::new(id.clone(), level);
-                    node.labels = labels.clone();
-
-                    // Find which bottom-level indices aggregate to this node
-                    for bottom_idx in 0..n_bottom {
-                        let matches = level_cols.iter().all(|col_name| {
-                            let bottom_series = bottom_df.column(col_name).unwrap();
-                            let bottom_val = bottom_series.get(bottom_idx).unwrap().to_string();
-                            let bottom_val = bottom_val.trim_matches('"');
-                            labels.get(col_name).map(|v| v.as_str()) == Some(bottom_val)
-                        });
-
-                        if matches {
-                            node.aggregates_from.push(bottom_idx);
-                        }
-                    }
+                    node.labels = labels;
+                    node.columns = level_cols.clone();
+                    node.bottom = level + 1 == n_levels;
 
-                    id_to_index.insert(id, nodes.len());
+                    // Labels containing "/" can join to another node's id
+                    if id_to_index.insert(id, nodes.len()).is_some() {
+                        return Err(HtsError::Hierarchy(format!(
+                            "Series id '{}' is produced by more than one label \
+                             combination; remove '/' from the labels or rename \
+                             the clashing values",
+                            node.id
+                        )));
+                    }
+                    key_to_index.insert(key, nodes.len());
                     nodes.push(node);
                 }
+
+                // Assign each bottom row to its node in a single pass
+                let level_bottom_labels: Vec<&Vec<String>> = level_cols
+                    .iter()
+                    .map(|name| &bottom_labels[name.as_str()])
+                    .collect();
+                for bottom_idx in 0..n_bottom {
+                    let key: Vec<&str> = level_bottom_labels
+                        .iter()
+                        .map(|labels| labels[bottom_idx].as_str())
+                        .collect();
+                    let node_idx = key_to_index[&key];
+                    nodes[node_idx].aggregates_from.push(bottom_idx);
+                }
             }
         }
 
@@ -324,14 +791,34 @@ impl HierarchyTree {
             n_bottom,
             n_levels,
             id_to_index,
+            bottom_keys: bottom_df,
         })
     }
 
     /// Returns the total number of series (all levels).
+    ///
+    /// Same as [`Self::len`].
     pub fn n_series(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns the number of nodes, one per series at every level.
+    pub fn len(&self) -> usize {
         self.nodes.len()
     }
 
+    /// Returns true if the tree has no nodes.
+    ///
+    /// A tree built from data always contains at least the total node.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns true if a node with the given ID exists.
+    pub fn contains(&self, id: &str) -> bool {
+        self.id_to_index.contains_key(id)
+    }
+
     /// Returns the number of bottom-level series.
     pub fn n_bottom(&self) -> usize {
         self.n_bottom
@@ -347,20 +834,449 @@ impl HierarchyTree {
         &self.nodes
     }
 
+    /// Returns the root node aggregating every series.
+    pub fn total_node(&self) -> &Node {
+        // The root is always built first
+        &self.nodes[0]
+    }
+
     /// Returns the node with the given ID, if it exists.
     pub fn get_node(&self, id: &str) -> Option<&Node> {
         self.id_to_index.get(id).map(|&idx| &self.nodes[idx])
     }
 
+    /// Returns the distinct combinations of the spec's columns, one row per
+    /// bottom-level series, in summation matrix column order.
+    ///
+    /// Row j holds the labels of column j of S, so attributes such as
+    /// population or area can be joined onto it to align them with the
+    /// bottom-level series.
+    pub fn bottom_key_frame(&self) -> &DataFrame {
+        &self.bottom_keys
+    }
+
+    /// Returns the node ids at each level, sorted within the level.
+    ///
+    /// Entry `k` holds the ids of level `k`, so the first entry is the total.
+    pub fn ids_by_level(&self) -> Vec<Vec<String>> {
+        let mut levels = vec![Vec::new(); self.n_levels];
+        for node in &self.nodes {
+            levels[node.level].push(node.id.clone());
+        }
+        for ids in &mut levels {
+            ids.sort_unstable();
+        }
+        levels
+    }
+
+    /// Returns the ids of all nodes, in node (summation matrix row) order.
+    pub fn all_ids(&self) -> Vec<&str> {
+        self.nodes.iter().map(|n| n.id.as_str()).collect()
+    }
+
+    /// Attaches the attribute `key` = `value` to the node `id`, replacing any
+    /// previous value, and returns whether the node exists.
+    pub fn set_node_meta(
+        &mut self,
+        id: &str,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> bool {
+        match self.id_to_index.get(id) {
+            Some(&idx) => {
+                self.nodes[idx].meta.insert(key.into(), value.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the attribute `key` of the node `id`, if both exist.
+    pub fn node_meta(&self, id: &str, key: &str) -> Option<&str> {
+        self.get_node(id)?.meta.get(key).map(String::as_str)
+    }
+
+    /// Renders the hierarchy as an indented ASCII tree, one node id per line,
+    /// with bottom-level nodes marked `[bottom]`.
+    ///
+    /// See [`Self::render_tree_with`] to limit the output for large trees.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::polars::prelude::*;
+    /// use hts_core::{HierarchySpec, HierarchyTree};
+    ///
+    /// let df = df! {
+    ///     "State" => ["SP", "SP", "RJ"],
+    ///     "City" => ["Campinas", "Santos", "Niteroi"],
+    /// }
+    /// .unwrap();
+    ///
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into(), "City".into()]);
+    /// let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+    ///
+    /// assert_eq!(
+    ///     tree.render_tree(),
+    ///     "Total
+    /// ├── RJ
+    /// │   └── RJ/Niteroi [bottom]
+    /// └── SP
+    ///     ├── SP/Campinas [bottom]
+    ///     └── SP/Santos [bottom]
+    /// "
+    /// );
+    /// ```
+    pub fn render_tree(&self) -> String {
+        self.render_tree_with(None, None)
+    }
+
+    /// Renders the hierarchy like [`Self::render_tree`], descending at most
+    /// `max_depth` levels below the total and listing at most `max_children`
+    /// children per node, followed by a `… n more` line for the rest.
+    ///
+    /// A node's children are the nodes of the next finer levels that share
+    /// its labels. In grouped structures a series has several parents, so it
+    /// is listed under each of them.
+    pub fn render_tree_with(
+        &self,
+        max_depth: Option<usize>,
+        max_children: Option<usize>,
+    ) -> String {
+        let renderer = TreeRenderer {
+            tree: self,
+            children: self.children_map(),
+            max_depth,
+            max_children,
+        };
+        let mut out = String::new();
+        renderer.render(0, 0, "", &mut out);
+        out
+    }
+
+    /// Returns the index of each node's immediate parent, `None` for the
+    /// total.
+    ///
+    /// The parent is the node aggregating this one at the nearest coarser
+    /// level. In grouped structures a node has several such parents (e.g.,
+    /// `"A/X"` rolls up into both state `"A"` and purpose `"X"`); the one
+    /// kept is the hierarchy parent, whose grouping columns are the longest
+    /// prefix of the node's (state `"A"` here). Indices follow
+    /// [`Self::nodes`].
+    pub fn parent_map(&self) -> Vec<Option<usize>> {
+        let level_columns = self.level_columns();
+        let child_levels = self.child_levels();
+
+        // Parent level of each level, preferring the longest column prefix
+        let parent_level: Vec<Option<usize>> = (0..self.n_levels)
+            .map(|level| {
+                (0..self.n_levels)
+                    .filter(|&coarse| child_levels[coarse].contains(&level))
+                    .max_by_key(|&coarse| {
+                        let prefix = level_columns[level].starts_with(level_columns[coarse]);
+                        (prefix, level_columns[coarse].len())
+                    })
+            })
+            .collect();
+
+        // Node index by level and labels in the level's column order
+        let key = |node: &'_ Node, columns: &[String]| -> Vec<String> {
+            columns.iter().map(|c| node.labels[c].clone()).collect()
+        };
+        let index: HashMap<(usize, Vec<String>), usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| ((node.level, key(node, &node.columns)), idx))
+            .collect();
+
+        self.nodes
+            .iter()
+            .map(|node| {
+                let parent = parent_level[node.level]?;
+                index
+                    .get(&(parent, key(node, level_columns[parent])))
+                    .copied()
+            })
+            .collect()
+    }
+
+    /// Returns the grouping columns of each level.
+    fn level_columns(&self) -> Vec<&[String]> {
+        let mut level_columns: Vec<&[String]> = vec![&[]; self.n_levels];
+        for node in &self.nodes {
+            level_columns[node.level] = &node.columns;
+        }
+        level_columns
+    }
+
+    /// Returns the indices of each node's children, the nodes of the next
+    /// finer levels sharing its labels, by level and then in node order.
+    ///
+    /// Unlike [`Self::parent_map`], a node of a grouped structure is listed
+    /// under each of its parents.
+    fn children_map(&self) -> Vec<Vec<usize>> {
+        let level_columns = self.level_columns();
+
+        // Node index by level and labels in the level's column order
+        let key = |node: &'_ Node, columns: &[String]| -> Vec<String> {
+            columns.iter().map(|c| node.labels[c].clone()).collect()
+        };
+        let index: HashMap<(usize, Vec<String>), usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| ((node.level, key(node, &node.columns)), idx))
+            .collect();
+
+        let mut level_nodes: Vec<Vec<usize>> = vec![Vec::new(); self.n_levels];
+        for (idx, node) in self.nodes.iter().enumerate() {
+            level_nodes[node.level].push(idx);
+        }
+
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        for (level, fine_levels) in self.child_levels().into_iter().enumerate() {
+            for fine in fine_levels {
+                for &child in &level_nodes[fine] {
+                    let labels = key(&self.nodes[child], level_columns[level]);
+                    if let Some(&parent) = index.get(&(level, labels)) {
+                        children[parent].push(child);
+                    }
+                }
+            }
+        }
+        children
+    }
+
+    /// Returns, for each level, the levels refining it with no level between.
+    fn child_levels(&self) -> Vec<Vec<usize>> {
+        let level_columns = self.level_columns();
+        let refines = |fine: &[String], coarse: &[String]| {
+            fine.len() > coarse.len() && coarse.iter().all(|c| fine.contains(c))
+        };
+        (0..self.n_levels)
+            .map(|level| {
+                (0..self.n_levels)
+                    .filter(|&fine| {
+                        refines(level_columns[fine], level_columns[level])
+                            && !(0..self.n_levels).any(|mid| {
+                                refines(level_columns[mid], level_columns[level])
+                                    && refines(level_columns[fine], level_columns[mid])
+                            })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Returns an iterator over bottom-level nodes.
     pub fn bottom_level_nodes(&self) -> impl Iterator<Item = &Node> {
         self.nodes.iter().filter(|n| n.is_bottom())
     }
+
+    /// Summarizes how evenly the tree branches.
+    ///
+    /// Parents and depths follow [`Self::parent_map`]: a node's children are
+    /// the nodes it is the parent of, and a node's depth is its number of
+    /// ancestors. The leaves are the bottom-level nodes. In grouped
+    /// structures, group-only nodes may have no children, as each bottom
+    /// node has a single parent in the map. Ragged hierarchies encoded with
+    /// repeated labels show up as single-child parents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::{HierarchySpec, HierarchyTree};
+    /// use hts_core::polars::prelude::*;
+    ///
+    /// let df = df! {
+    ///     "State" => ["NSW", "NSW", "NSW", "ACT"],
+    ///     "Region" => ["Sydney", "Hunter", "Riverina", "Canberra"],
+    /// }.unwrap();
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+    /// let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+    ///
+    /// let report = tree.balance_report();
+    /// assert_eq!((report.min_children, report.max_children), (1, 3));
+    /// assert_eq!(report.single_child_parents, 1);
+    /// assert!(report.uniform_leaf_depth);
+    /// ```
+    pub fn balance_report(&self) -> BalanceReport {
+        let parents = self.parent_map();
+        let mut n_children = vec![0usize; self.nodes.len()];
+        for &parent in parents.iter().flatten() {
+            n_children[parent] += 1;
+        }
+
+        let depth = |mut idx: usize| {
+            let mut depth = 0;
+            while let Some(parent) = parents[idx] {
+                depth += 1;
+                idx = parent;
+            }
+            depth
+        };
+
+        let counts: Vec<usize> = n_children.iter().copied().filter(|&c| c > 0).collect();
+        let leaf_depths: Vec<usize> = (0..self.nodes.len())
+            .filter(|&idx| self.nodes[idx].is_bottom())
+            .map(depth)
+            .collect();
+
+        BalanceReport {
+            n_parents: counts.len(),
+            min_children: counts.iter().copied().min().unwrap_or(0),
+            max_children: counts.iter().copied().max().unwrap_or(0),
+            mean_children: if counts.is_empty() {
+                0.0
+            } else {
+                counts.iter().sum::<usize>() as f64 / counts.len() as f64
+            },
+            single_child_parents: counts.iter().filter(|&&c| c == 1).count(),
+            min_leaf_depth: leaf_depths.iter().copied().min().unwrap_or(0),
+            max_leaf_depth: leaf_depths.iter().copied().max().unwrap_or(0),
+            uniform_leaf_depth: leaf_depths.windows(2).all(|w| w[0] == w[1]),
+        }
+    }
+}
+
+/// How evenly a [`HierarchyTree`] branches, from
+/// [`HierarchyTree::balance_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceReport {
+    /// Number of nodes with at least one child.
+    pub n_parents: usize,
+    /// Fewest children of any parent (0 if there are no parents).
+    pub min_children: usize,
+    /// Most children of any parent (0 if there are no parents).
+    pub max_children: usize,
+    /// Mean number of children per parent (0 if there are no parents).
+    pub mean_children: f64,
+    /// Number of parents with exactly one child.
+    pub single_child_parents: usize,
+    /// Depth of the shallowest leaf (the root has depth 0).
+    pub min_leaf_depth: usize,
+    /// Depth of the deepest leaf.
+    pub max_leaf_depth: usize,
+    /// Whether every leaf is at the same depth.
+    pub uniform_leaf_depth: bool,
+}
+
+/// State shared while rendering [`HierarchyTree::render_tree_with`].
+struct TreeRenderer<'a> {
+    tree: &'a HierarchyTree,
+    children: Vec<Vec<usize>>,
+    max_depth: Option<usize>,
+    max_children: Option<usize>,
+}
+
+impl TreeRenderer<'_> {
+    /// Writes node `idx` (its line already prefixed) and its subtree, with
+    /// `indent` prepended to every child line.
+    fn render(&self, idx: usize, depth: usize, indent: &str, out: &mut String) {
+        let node = &self.tree.nodes[idx];
+        out.push_str(&node.id);
+        if node.level + 1 == self.tree.n_levels {
+            out.push_str(" [bottom]");
+        }
+        out.push('\n');
+
+        if self.max_depth.is_some_and(|max| depth >= max) {
+            return;
+        }
+
+        let children = &self.children[idx];
+        let shown = self
+            .max_children
+            .map_or(children.len(), |max| max.min(children.len()));
+        let hidden = children.len() - shown;
+
+        for (i, &child) in children[..shown].iter().enumerate() {
+            let last = i + 1 == shown && hidden == 0;
+            out.push_str(indent);
+            out.push_str(if last { "└── " } else { "├── " });
+            let child_indent = format!("{indent}{}", if last { "    " } else { "│   " });
+            self.render(child, depth + 1, &child_indent, out);
+        }
+        if hidden > 0 {
+            out.push_str(&format!("{indent}└── … {hidden} more\n"));
+        }
+    }
+}
+
+/// Reads the values of `name` in `df` as node labels.
+fn column_labels(df: &DataFrame, name: &str) -> Result<Vec<String>> {
+    let series = df.column(name)?.as_materialized_series();
+    Ok(series
+        .iter()
+        .map(|value| value.to_string().trim_matches('"').to_string())
+        .collect())
+}
+
+/// Sorts the rows of `df` by `columns`, with text columns in `collation`
+/// order.
+///
+/// Other columns keep Polars' order (numbers by value, dates
+/// chronologically); the default collation leaves `df` untouched.
+pub(crate) fn sort_labels(
+    df: DataFrame,
+    columns: &[&str],
+    collation: Collation,
+) -> Result<DataFrame> {
+    if collation == Collation::default() || df.height() < 2 {
+        return Ok(df);
+    }
+
+    // Rank of each row in each column, equal labels sharing a rank
+    let mut ranks: Vec<Vec<usize>> = Vec::with_capacity(columns.len());
+    for &name in columns {
+        let column = df.column(name)?;
+        let labels = column_labels(&df, name)?;
+        let order: Vec<usize> = if column.dtype() == &DataType::String {
+            let mut order: Vec<usize> = (0..df.height()).collect();
+            order.sort_by(|&a, &b| collation.compare(&labels[a], &labels[b]));
+            order
+        } else {
+            column
+                .as_materialized_series()
+                .arg_sort(SortOptions::default())
+                .into_no_null_iter()
+                .map(|i| i as usize)
+                .collect()
+        };
+        let mut rank = vec![0; df.height()];
+        for k in 1..order.len() {
+            let same = labels[order[k]] == labels[order[k - 1]];
+            rank[order[k]] = if same { rank[order[k - 1]] } else { k };
+        }
+        ranks.push(rank);
+    }
+
+    let mut order: Vec<usize> = (0..df.height()).collect();
+    order.sort_by(|&a, &b| {
+        ranks
+            .iter()
+            .map(|rank| rank[a].cmp(&rank[b]))
+            .find(|o| o.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let idx = IdxCa::from_vec("".into(), order.into_iter().map(|i| i as IdxSize).collect());
+    Ok(df.take(&idx)?)
+}
+
+impl<'a> IntoIterator for &'a HierarchyTree {
+    type Item = &'a Node;
+    type IntoIter = std::slice::Iter<'a, Node>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.iter()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::summation_matrix::SummationMatrix;
 
     fn sample_df() -> DataFrame {
         df! {
@@ -383,6 +1299,16 @@ mod tests {
 
         let bad_spec = HierarchySpec::new(vec!["NonExistent".into()], vec![]);
         assert!(bad_spec.validate(&df).is_err());
+
+        let overlapping = HierarchySpec::new(vec!["State".into()], vec!["State".into()]);
+        assert!(matches!(
+            overlapping.validate(&df),
+            Err(HtsError::Hierarchy(_))
+        ));
+        assert!(HierarchyTree::from_dataframe(&df, &overlapping).is_err());
+
+        let repeated = HierarchySpec::grouped(vec!["Purpose".into(), "Purpose".into()]);
+        assert!(repeated.validate(&df).is_err());
     }
 
     #[test]
@@ -404,5 +1330,559 @@ mod tests {
         // Total should aggregate all bottom series
         let total = tree.get_node("Total").unwrap();
         assert_eq!(total.aggregates_from.len(), 4);
+        assert!(total.is_total());
+        assert_eq!(tree.total_node().id, total.id);
+        assert_eq!(tree.nodes().iter().filter(|n| n.is_total()).count(), 1);
+    }
+
+    #[test]
+    fn test_custom_total_label() {
+        let df = df! {
+            "State" => ["Rio de Janeiro", "São Paulo"],
+            "Value" => [1.0, 2.0],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]).with_total_label("Brasil");
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+
+        assert!(tree.get_node("Total").is_none());
+        let total = tree.get_node("Brasil").unwrap();
+        assert!(total.is_total());
+        assert_eq!(tree.total_node().id, "Brasil");
+        assert_eq!(total.level, 0);
+        assert_eq!(total.aggregates_from.len(), 2);
+    }
+
+    #[test]
+    fn test_tree_conveniences() {
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&sample_df(), &spec).unwrap();
+
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.len(), tree.n_series());
+        assert!(!tree.is_empty());
+        assert!(tree.contains("A/A1"));
+        assert!(!tree.contains("C"));
+
+        let mut ids = Vec::new();
+        for node in &tree {
+            ids.push(node.id.as_str());
+        }
+        assert_eq!(ids[0], "Total");
+        assert_eq!(ids.len(), tree.len());
+    }
+
+    #[test]
+    fn test_duplicate_ids_rejected() {
+        let df = df! {
+            "State" => ["A/B", "A"],
+            "Region" => ["C", "B/C"],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let err = HierarchyTree::from_dataframe(&df, &spec).unwrap_err();
+        assert!(matches!(&err, HtsError::Hierarchy(msg) if msg.contains("A/B/C")));
+
+        // A label equal to the total label collides with the root
+        let df = df! { "State" => ["Total", "X"] }.unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        assert!(matches!(
+            HierarchyTree::from_dataframe(&df, &spec),
+            Err(HtsError::Hierarchy(_))
+        ));
+    }
+
+    #[test]
+    fn test_ids_by_level() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+
+        let levels = tree.ids_by_level();
+        assert_eq!(levels.len(), tree.n_levels());
+        assert_eq!(levels[0], vec!["Total".to_string()]);
+        for ids in &levels {
+            assert!(ids.windows(2).all(|w| w[0] < w[1]), "{ids:?}");
+        }
+
+        let all = tree.all_ids();
+        assert_eq!(all.len(), tree.len());
+        assert_eq!(all.len(), levels.iter().map(Vec::len).sum::<usize>());
+        assert_eq!(all[0], "Total");
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+
+        let expected = "\
+Total
+├── A
+│   ├── A/A1 [bottom]
+│   └── A/A2 [bottom]
+└── B
+    ├── B/B1 [bottom]
+    └── B/B2 [bottom]
+";
+        assert_eq!(tree.render_tree(), expected);
+        assert_eq!(
+            tree.render_tree_with(Some(1), None),
+            "Total\n├── A\n└── B\n"
+        );
+        assert_eq!(
+            tree.render_tree_with(Some(1), Some(1)),
+            "Total\n├── A\n└── … 1 more\n"
+        );
+
+        // Grouped: each bottom series is listed under both of its parents
+        let spec = HierarchySpec::new(vec!["State".into()], vec!["Purpose".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let expected = "\
+Total
+├── X
+│   ├── A/X [bottom]
+│   └── B/X [bottom]
+├── A
+│   └── A/X [bottom]
+└── B
+    └── B/X [bottom]
+";
+        assert_eq!(tree.render_tree(), expected);
+    }
+
+    #[test]
+    fn test_parent_map() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let parents = tree.parent_map();
+        let parent_id = |id: &str| {
+            let idx = tree.nodes().iter().position(|n| n.id == id).unwrap();
+            parents[idx].map(|p| tree.nodes()[p].id.as_str())
+        };
+        assert_eq!(parent_id("Total"), None);
+        assert_eq!(parent_id("A"), Some("Total"));
+        assert_eq!(parent_id("B/B2"), Some("B"));
+
+        // Grouped: the hierarchy parent wins over the group parent
+        let spec = HierarchySpec::new(vec!["State".into()], vec!["Purpose".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let parents = tree.parent_map();
+        let parent_id = |id: &str| {
+            let idx = tree.nodes().iter().position(|n| n.id == id).unwrap();
+            parents[idx].map(|p| tree.nodes()[p].id.as_str())
+        };
+        assert_eq!(parent_id("X"), Some("Total"));
+        assert_eq!(parent_id("A/X"), Some("A"));
+        assert_eq!(parents.iter().filter(|p| p.is_none()).count(), 1);
+    }
+
+    #[test]
+    fn test_collation() {
+        let df = df! {
+            "Store" => ["10", "2", "b", "A", "a"],
+            "Year" => [2010i64, 2010, 2009, 2010, 2009],
+        }
+        .unwrap();
+        let spec = HierarchySpec::new(vec!["Store".into()], vec!["Year".into()]);
+        let bottom = |collation| {
+            HierarchyTree::from_dataframe_with_collation(&df, &spec, collation)
+                .unwrap()
+                .bottom_level_nodes()
+                .map(|n| n.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            bottom(Collation::CaseSensitive),
+            ["10/2010", "2/2010", "A/2010", "a/2009", "b/2009"]
+        );
+        assert_eq!(
+            bottom(Collation::CaseInsensitive),
+            ["10/2010", "2/2010", "A/2010", "a/2009", "b/2009"]
+        );
+        assert_eq!(
+            bottom(Collation::Numeric),
+            ["2/2010", "10/2010", "A/2010", "a/2009", "b/2009"]
+        );
+        assert_eq!(
+            HierarchyTree::from_dataframe(&df, &spec)
+                .unwrap()
+                .ids_by_level(),
+            HierarchyTree::from_dataframe_with_collation(&df, &spec, Collation::CaseSensitive)
+                .unwrap()
+                .ids_by_level()
+        );
+
+        // Levels are sorted too; the integer group keeps its numeric order
+        let tree =
+            HierarchyTree::from_dataframe_with_collation(&df, &spec, Collation::Numeric).unwrap();
+        let level = |k: usize| -> Vec<&str> {
+            tree.nodes()
+                .iter()
+                .filter(|n| n.level == k)
+                .map(|n| n.id.as_str())
+                .collect()
+        };
+        assert_eq!(level(1), ["2009", "2010"]);
+        assert_eq!(level(2), ["2", "10", "A", "a", "b"]);
+    }
+
+    #[test]
+    fn test_balance_report() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let report = HierarchyTree::from_dataframe(&df, &spec)
+            .unwrap()
+            .balance_report();
+        assert_eq!(
+            report,
+            BalanceReport {
+                n_parents: 3,
+                min_children: 2,
+                max_children: 2,
+                mean_children: 2.0,
+                single_child_parents: 0,
+                min_leaf_depth: 2,
+                max_leaf_depth: 2,
+                uniform_leaf_depth: true,
+            }
+        );
+
+        // Total -> {A, B, C}; A -> 3 regions, B -> 1, C -> 2
+        let df = df! {
+            "State" => ["A", "A", "A", "B", "C", "C"],
+            "Region" => ["A1", "A2", "A3", "B1", "C1", "C2"],
+        }
+        .unwrap();
+        let report = HierarchyTree::from_dataframe(&df, &spec)
+            .unwrap()
+            .balance_report();
+        assert_eq!(report.n_parents, 4);
+        assert_eq!((report.min_children, report.max_children), (1, 3));
+        assert_eq!(report.mean_children, 9.0 / 4.0);
+        assert_eq!(report.single_child_parents, 1);
+
+        // Grouped: every bottom series hangs off its state
+        let spec = HierarchySpec::new(vec!["State".into()], vec!["Purpose".into()]);
+        let report = HierarchyTree::from_dataframe(&sample_df(), &spec)
+            .unwrap()
+            .balance_report();
+        assert!(report.uniform_leaf_depth);
+        assert_eq!((report.min_leaf_depth, report.max_leaf_depth), (2, 2));
+        // Total -> {A, B, X}, A -> A/X, B -> B/X; X has no child in the map
+        assert_eq!(report.n_parents, 3);
+        assert_eq!(report.single_child_parents, 2);
+    }
+
+    #[test]
+    fn test_from_mapping() {
+        let bottom = df! {
+            "Code" => [11i64, 12, 21, 11],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let mapping = df! {
+            "Code" => ["21", "11", "12", "99"],
+            "Region" => ["R2", "R1", "R1", "R9"],
+            "State" => ["S1", "S1", "S1", "S9"],
+        }
+        .unwrap();
+
+        let joined =
+            HierarchyTree::join_mapping(&bottom, &mapping, "Code", &["State", "Region"]).unwrap();
+        assert_eq!(joined.height(), 4);
+        assert_eq!(
+            joined.column("Region").unwrap().str().unwrap().get(3),
+            Some("R1")
+        );
+
+        let tree =
+            HierarchyTree::from_mapping(&bottom, &mapping, "Code", &["State", "Region"]).unwrap();
+        assert_eq!(tree.n_bottom(), 3);
+        assert_eq!(tree.n_levels(), 4);
+        assert!(tree.contains("S1/R1/12"));
+        assert_eq!(tree.get_node("S1/R1").unwrap().aggregates_from.len(), 2);
+
+        // Unmapped and ambiguous codes
+        let partial = mapping.slice(0, 2);
+        assert!(matches!(
+            HierarchyTree::from_mapping(&bottom, &partial, "Code", &["State", "Region"]),
+            Err(HtsError::Hierarchy(msg)) if msg.contains("12")
+        ));
+        let doubled = mapping.vstack(&mapping.slice(0, 1)).unwrap();
+        assert!(matches!(
+            HierarchyTree::from_mapping(&bottom, &doubled, "Code", &["State", "Region"]),
+            Err(HtsError::Hierarchy(_))
+        ));
+        assert!(matches!(
+            HierarchyTree::from_mapping(&bottom, &mapping, "Code", &["Country"]),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_group_order_is_not_significant() {
+        let df = df! {
+            "State" => ["A", "A", "B"],
+            "Purpose" => ["X", "Y", "X"],
+            "Mode" => ["Car", "Air", "Air"],
+        }
+        .unwrap();
+        let spec = HierarchySpec::new(vec!["State".into()], vec!["Purpose".into(), "Mode".into()]);
+        let swapped =
+            HierarchySpec::new(vec!["State".into()], vec!["Mode".into(), "Purpose".into()]);
+        assert_eq!(spec, swapped);
+        assert_eq!(spec.level_combinations(), swapped.level_combinations());
+
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let other = HierarchyTree::from_dataframe(&df, &swapped).unwrap();
+        assert_eq!(tree.all_ids(), other.all_ids());
+        assert!(tree.contains("A/Car/X"));
+        assert_eq!(
+            SummationMatrix::from_hierarchy(&tree).to_vec(),
+            SummationMatrix::from_hierarchy(&other).to_vec()
+        );
+
+        // The hierarchy order does matter
+        let inverted =
+            HierarchySpec::new(vec!["Purpose".into()], vec!["Mode".into(), "State".into()]);
+        assert_ne!(spec, inverted);
+    }
+
+    #[test]
+    fn test_single_bottom_series() {
+        let df = df! {
+            "State" => ["A", "A"],
+            "Value" => [1.0, 2.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+
+        // The total aggregates a single series but is not a bottom node
+        assert_eq!(tree.total_node().aggregates_from, vec![0]);
+        assert!(!tree.total_node().is_bottom());
+        let bottom: Vec<&str> = tree.bottom_level_nodes().map(|n| n.id.as_str()).collect();
+        assert_eq!(bottom, vec!["A"]);
+
+        let s = SummationMatrix::from_hierarchy(&tree);
+        assert_eq!(s.shape(), (2, 1));
+        assert_eq!(s.col_labels(), ["A".to_string()]);
+        assert!(s.verify_structure().is_ok());
+    }
+
+    #[test]
+    fn test_single_child_is_not_bottom() {
+        let df = df! {
+            "State" => ["A", "B", "B"],
+            "Region" => ["A1", "B1", "B2"],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+
+        // State A aggregates exactly one series, its only region
+        let state = tree.get_node("A").unwrap();
+        assert_eq!(state.aggregates_from.len(), 1);
+        assert!(!state.is_bottom());
+
+        let s = SummationMatrix::from_hierarchy(&tree);
+        assert_eq!(s.n_bottom(), 3);
+        assert_eq!(s.col_labels(), ["A/A1", "B/B1", "B/B2"].map(String::from));
+        assert!(s.verify_structure().is_ok());
+    }
+
+    #[test]
+    fn test_bottom_key_frame() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+
+        let keys = tree.bottom_key_frame();
+        assert_eq!(keys.height(), s.n_bottom());
+        assert_eq!(keys.get_column_names(), ["State", "Region"]);
+
+        let states = keys.column("State").unwrap().str().unwrap();
+        let regions = keys.column("Region").unwrap().str().unwrap();
+        for (j, id) in s.col_labels().iter().enumerate() {
+            let key = format!("{}/{}", states.get(j).unwrap(), regions.get(j).unwrap());
+            assert_eq!(&key, id);
+        }
+    }
+
+    #[test]
+    fn test_node_meta() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let mut tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+
+        assert!(tree.set_node_meta("A", "population", "1200"));
+        assert!(tree.set_node_meta("A", "population", "1300"));
+        assert!(!tree.set_node_meta("C", "population", "1"));
+        assert_eq!(tree.node_meta("A", "population"), Some("1300"));
+        assert_eq!(tree.node_meta("A", "manager"), None);
+        assert!(
+            !tree
+                .get_node("A")
+                .unwrap()
+                .labels
+                .contains_key("population")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_tree_serde() {
+        let df = sample_df();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let mut tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        tree.set_node_meta("A", "population", "1200");
+
+        let json = serde_json::to_string(&tree).unwrap();
+        let back: HierarchyTree = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.node_meta("A", "population"), Some("1200"));
+        assert_eq!(back.all_ids(), tree.all_ids());
+        assert!(back.bottom_key_frame().equals(tree.bottom_key_frame()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_node_serde() {
+        let mut node = Node::new("A".into(), 1);
+        node.meta.insert("population".into(), "1200".into());
+
+        let json = serde_json::to_string(&node).unwrap();
+        let back: Node = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.meta, node.meta);
+        assert_eq!(back.id, "A");
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("bottom");
+        assert!(serde_json::from_value::<Node>(value).is_err());
+    }
+
+    #[test]
+    fn test_structure_kind() {
+        let columns = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            HierarchySpec::hierarchical(columns(&["State", "Region"])).kind(),
+            StructureKind::Hierarchical
+        );
+        assert_eq!(
+            HierarchySpec::grouped(columns(&["Purpose"])).kind(),
+            StructureKind::Grouped
+        );
+        assert_eq!(
+            HierarchySpec::new(columns(&["State"]), columns(&["Purpose"])).kind(),
+            StructureKind::Mixed
+        );
+        assert_eq!(HierarchySpec::default().kind(), StructureKind::Hierarchical);
+    }
+
+    #[test]
+    fn test_check_order() {
+        let df = sample_df();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        assert!(spec.check_order(&df).is_ok());
+
+        let upside_down = HierarchySpec::hierarchical(vec!["Region".into(), "State".into()]);
+        let err = upside_down.check_order(&df).unwrap_err();
+        assert!(err.to_string().contains("'State'"), "{err}");
+
+        let missing = HierarchySpec::hierarchical(vec!["Country".into()]);
+        assert!(matches!(
+            missing.check_order(&df),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_canonical_ids_mixed_spec() {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["A1", "A2", "B1", "B2"],
+            "Purpose" => ["X", "Y", "X", "Y"],
+            "Mode" => ["Air", "Air", "Road", "Road"],
+        }
+        .unwrap();
+
+        // Groups listed unsorted: ids still use them sorted by name, so Mode
+        // comes before Purpose
+        let spec = HierarchySpec::new(
+            vec!["State".into(), "Region".into()],
+            vec!["Purpose".into(), "Mode".into()],
+        );
+
+        for level in spec.level_combinations() {
+            let positions: Vec<usize> = level
+                .iter()
+                .map(|c| spec.all_columns().iter().position(|a| a == c).unwrap())
+                .collect();
+            assert!(positions.windows(2).all(|w| w[0] < w[1]), "{level:?}");
+        }
+
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let ids: Vec<&str> = tree.nodes().iter().map(|n| n.id.as_str()).collect();
+
+        // Hierarchy components first, then groups sorted by name
+        assert!(ids.contains(&"A/Air/X"));
+        assert!(ids.contains(&"A/A1/Air/X"));
+        assert!(ids.contains(&"Air/X"));
+        assert!(!ids.contains(&"X/Air"));
+        assert!(!ids.contains(&"Air/A/X"));
+
+        // Ids are unique across levels
+        let unique: HashSet<&str> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        for node in tree.nodes() {
+            assert_eq!(spec.series_id(&node.labels), node.id);
+        }
+
+        let node = tree.get_node("B/B2/Road/Y").unwrap();
+        assert_eq!(
+            node.path(),
+            vec![
+                ("State", "B"),
+                ("Region", "B2"),
+                ("Mode", "Road"),
+                ("Purpose", "Y")
+            ]
+        );
+        assert!(tree.total_node().path().is_empty());
+    }
+
+    #[test]
+    fn test_deep_hierarchy() {
+        // A binary hierarchy twelve levels deep: 4096 bottom series
+        let depth = 12;
+        let n_bottom = 1usize << depth;
+        let columns: Vec<Column> = (1..=depth)
+            .map(|d| {
+                let labels: Vec<String> = (0..n_bottom)
+                    .map(|b| format!("{:0d$b}", b >> (depth - d)))
+                    .collect();
+                Column::new(format!("L{d}").into(), labels)
+            })
+            .collect();
+        let df = DataFrame::new(columns).unwrap();
+        let spec = HierarchySpec::hierarchical((1..=depth).map(|d| format!("L{d}")).collect());
+
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        assert_eq!(tree.n_levels(), depth + 1);
+        assert_eq!(tree.n_bottom(), n_bottom);
+        assert_eq!(tree.len(), 2 * n_bottom - 1);
+
+        // Each node aggregates exactly its subtree
+        for node in &tree {
+            assert_eq!(node.aggregates_from.len(), n_bottom >> node.level);
+        }
     }
 }
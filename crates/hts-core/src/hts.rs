@@ -20,12 +20,27 @@
 //! This module provides the main `HierarchicalTimeSeries` type that combines
 //! bottom-level data with the hierarchy structure and summation matrix.
 
+use crate::config::{HtsConfig, NaPeriodPolicy};
 use crate::error::{HtsError, Result};
-use crate::hierarchy::{HierarchySpec, HierarchyTree};
+use crate::hierarchy::{HierarchySpec, HierarchyTree, Node};
 use crate::period::Period;
+use crate::reconciliation::{
+    LevelAccuracy, ReconContext, ReconMethod, ReconReport, ReconciliationMatrix, TopDownMethod,
+};
 use crate::summation_matrix::SummationMatrix;
+use crate::tolerance::Tolerance;
+use faer::Mat;
 use polars::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Placeholder label filling key columns that are aggregated over in the
+/// output of [`HierarchicalTimeSeries::aggregate_all`].
+pub const AGGREGATED_LABEL: &str = "<aggregated>";
 
 /// A hierarchical and/or grouped time series dataset.
 ///
@@ -75,11 +90,20 @@ pub struct HierarchicalTimeSeries {
 
     /// Name of the value column.
     value_col: String,
+
+    /// Options used to build this dataset.
+    config: HtsConfig,
+
+    /// Thread pool of [`HtsConfig::n_threads`], shared by derived datasets.
+    pool: Option<Arc<ThreadPool>>,
 }
 
 impl HierarchicalTimeSeries {
     /// Creates a new `HierarchicalTimeSeries` from a DataFrame.
     ///
+    /// Uses the default [`HtsConfig`]; see [`Self::with_config`] to customize
+    /// how the data is loaded.
+    ///
     /// # Arguments
     ///
     /// * `bottom_data` - DataFrame containing the bottom-level time series
@@ -96,6 +120,263 @@ impl HierarchicalTimeSeries {
         time_col: &str,
         value_col: &str,
     ) -> Result<Self> {
+        Self::with_config(bottom_data, spec, time_col, value_col, HtsConfig::default())
+    }
+
+    /// Creates a new `HierarchicalTimeSeries` from a DataFrame with custom options.
+    ///
+    /// # Arguments
+    ///
+    /// * `bottom_data` - DataFrame containing the bottom-level time series
+    /// * `spec` - Hierarchy specification
+    /// * `time_col` - Name of the time/period column
+    /// * `value_col` - Name of the value column
+    /// * `config` - Construction options
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if columns are missing or data is invalid, including
+    /// blank time values when `config.na_period` is [`NaPeriodPolicy::Error`].
+    /// Returns [`HtsError::Hierarchy`] if the time or value column is listed
+    /// in the spec, and [`HtsError::Io`] if the threads of
+    /// [`HtsConfig::n_threads`] cannot be spawned.
+    ///
+    /// An integer time column (e.g., years as `i64`) is converted to strings
+    /// once here, so aggregated frames always carry string time values.
+    /// Duplicate (bottom key, period) rows are collapsed here when
+    /// [`HtsConfig::pre_aggregate`] is set.
+    pub fn with_config(
+        bottom_data: DataFrame,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: HtsConfig,
+    ) -> Result<Self> {
+        Ok(Self::build(bottom_data, spec, time_col, value_col, config)?.0)
+    }
+
+    /// Creates a new `HierarchicalTimeSeries` as [`Self::with_config`] does,
+    /// also reporting how long the expensive build steps took.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::{HierarchicalTimeSeries, HierarchySpec, HtsConfig};
+    /// use hts_core::polars::prelude::*;
+    ///
+    /// let df = df! {
+    ///     "Quarter" => ["1998 Q1", "1998 Q1"],
+    ///     "State" => ["A", "B"],
+    ///     "Trips" => [1.0, 2.0],
+    /// }.unwrap();
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+    ///
+    /// let (hts, diagnostics) = HierarchicalTimeSeries::new_with_diagnostics(
+    ///     df, spec, "Quarter", "Trips", HtsConfig::default(),
+    /// ).unwrap();
+    /// assert_eq!(diagnostics.n_nodes, hts.n_series());
+    /// assert_eq!(diagnostics.n_edges, 2);
+    /// println!("{diagnostics}");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::with_config`].
+    pub fn new_with_diagnostics(
+        bottom_data: DataFrame,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: HtsConfig,
+    ) -> Result<(Self, BuildDiagnostics)> {
+        let (hts, mut diagnostics) = Self::build(bottom_data, spec, time_col, value_col, config)?;
+        // Counted here rather than in `build`, so that plain construction
+        // does not pay for the parent lookup
+        diagnostics.n_edges = hts.tree.parent_map().iter().flatten().count();
+        Ok((hts, diagnostics))
+    }
+
+    /// Creates a new `HierarchicalTimeSeries` as [`Self::with_config`] does,
+    /// but reports every problem found in the input instead of the first.
+    ///
+    /// Before building, the input is checked for columns listed more than
+    /// once in the spec, missing grouping, time, value or weight columns, the
+    /// time or value column appearing in the spec, blank periods (under
+    /// [`NaPeriodPolicy::Error`]) and unparseable periods, each with its row,
+    /// and series ids produced by more than one label combination. If none
+    /// is found, the dataset is built, and a failure there is returned on its
+    /// own. The input is accepted exactly when [`Self::with_config`] accepts
+    /// it; prefer that fail-fast constructor when one error at a time will do.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::{HierarchicalTimeSeries, HierarchySpec, HtsConfig};
+    /// use hts_core::polars::prelude::*;
+    ///
+    /// let df = df! {
+    ///     "Quarter" => ["1998 Q1", "1998 Q5", "soon"],
+    ///     "State" => ["A", "B", "C"],
+    ///     "Trips" => [1.0, 2.0, 3.0],
+    /// }.unwrap();
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+    ///
+    /// let errors = HierarchicalTimeSeries::new_validated(
+    ///     df, spec, "Quarter", "Trips", HtsConfig::default(),
+    /// ).unwrap_err();
+    /// // The missing Region column and both bad periods
+    /// assert_eq!(errors.len(), 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found, in the order listed above, or the single
+    /// error of [`Self::with_config`] if the checks pass but building fails.
+    pub fn new_validated(
+        bottom_data: DataFrame,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: HtsConfig,
+    ) -> std::result::Result<Self, Vec<HtsError>> {
+        let problems = Self::validation_problems(&bottom_data, &spec, time_col, value_col, &config);
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+        Self::with_config(bottom_data, spec, time_col, value_col, config).map_err(|e| vec![e])
+    }
+
+    /// Collects the problems reported by [`Self::new_validated`].
+    fn validation_problems(
+        bottom_data: &DataFrame,
+        spec: &HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: &HtsConfig,
+    ) -> Vec<HtsError> {
+        let mut problems = Vec::new();
+        let all_cols = spec.all_columns();
+
+        let mut required: Vec<&str> = Vec::new();
+        let mut repeated = HashSet::new();
+        for &name in &all_cols {
+            if !required.contains(&name) {
+                required.push(name);
+            } else if repeated.insert(name) {
+                problems.push(HtsError::Hierarchy(format!(
+                    "Column '{name}' is listed more than once in the hierarchy specification"
+                )));
+            }
+        }
+        let spec_ok = repeated.is_empty();
+
+        required.extend([time_col, value_col]);
+        if !config.value_semantics.is_additive()
+            && let Some(weight_col) = &config.weight_col
+        {
+            required.push(weight_col);
+        }
+        let mut columns_ok = true;
+        for name in required {
+            if bottom_data.column(name).is_err() {
+                columns_ok = false;
+                problems.push(HtsError::ColumnNotFound(name.to_string()));
+            }
+        }
+        for name in [time_col, value_col] {
+            if all_cols.contains(&name) {
+                problems.push(HtsError::Hierarchy(format!(
+                    "Column '{name}' is the time or value column and cannot be part of the \
+                     hierarchy spec"
+                )));
+            }
+        }
+
+        if let Ok(times) = bottom_data.column(time_col)
+            && let Err(e) = Self::period_problems(times, config, &mut problems)
+        {
+            problems.push(e);
+        }
+
+        if spec_ok && columns_ok {
+            match spec.clashing_ids(bottom_data) {
+                Ok(clashes) => problems.extend(clashes.into_iter().map(|id| {
+                    HtsError::Hierarchy(format!(
+                        "Series id '{id}' is produced by more than one label combination; \
+                         remove '/' from the labels or rename the clashing values"
+                    ))
+                })),
+                Err(e) => problems.push(e),
+            }
+        }
+
+        problems
+    }
+
+    /// Appends an error for every blank (under [`NaPeriodPolicy::Error`])
+    /// or unparseable value of the time column.
+    fn period_problems(
+        times: &Column,
+        config: &HtsConfig,
+        problems: &mut Vec<HtsError>,
+    ) -> Result<()> {
+        let mut times = times.clone();
+        if !config.null_values.is_empty() {
+            let name = times.name().to_string();
+            let mut df = DataFrame::new(vec![times])?;
+            Self::apply_null_values(&mut df, &name, &config.null_values, false)?;
+            times = df.column(&name)?.clone();
+        }
+
+        if config.na_period == NaPeriodPolicy::Error {
+            for (row, blank) in Self::blank_periods(&times)?.into_iter().enumerate() {
+                if blank {
+                    problems.push(HtsError::InvalidPeriod(format!(
+                        "Empty value in time column '{}' at row {row} \
+                         (use NaPeriodPolicy::Drop to skip such rows)",
+                        times.name()
+                    )));
+                }
+            }
+        }
+
+        if matches!(times.dtype(), DataType::Date | DataType::Datetime(..)) {
+            return Ok(());
+        }
+        let strings = times.cast(&DataType::String)?;
+        let mut seen = HashSet::new();
+        for (row, value) in strings.str()?.into_iter().enumerate() {
+            let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            if seen.insert(value)
+                && let Err(e) = Period::parse_as(value, config.period_format)
+            {
+                problems.push(Self::locate_period_error(e, row, times.name()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the row and column to a period parsing error.
+    fn locate_period_error(e: HtsError, row: usize, column: &str) -> HtsError {
+        match e {
+            HtsError::InvalidPeriod(msg) => {
+                HtsError::InvalidPeriod(format!("{msg} (row {row} of column '{column}')"))
+            }
+            other => other,
+        }
+    }
+
+    /// Shared implementation of [`Self::with_config`] and
+    /// [`Self::new_with_diagnostics`]. The diagnostics leave `n_edges` at 0.
+    fn build(
+        mut bottom_data: DataFrame,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: HtsConfig,
+    ) -> Result<(Self, BuildDiagnostics)> {
         // Validate columns exist
         if bottom_data.column(time_col).is_err() {
             return Err(HtsError::ColumnNotFound(time_col.to_string()));
@@ -103,18 +384,140 @@ impl HierarchicalTimeSeries {
         if bottom_data.column(value_col).is_err() {
             return Err(HtsError::ColumnNotFound(value_col.to_string()));
         }
+        if !config.value_semantics.is_additive()
+            && let Some(weight_col) = &config.weight_col
+            && bottom_data.column(weight_col).is_err()
+        {
+            return Err(HtsError::ColumnNotFound(weight_col.clone()));
+        }
+        for name in [time_col, value_col] {
+            if spec.all_columns().contains(&name) {
+                return Err(HtsError::Hierarchy(format!(
+                    "Column '{name}' is the time or value column and cannot be part of the \
+                     hierarchy spec"
+                )));
+            }
+        }
+
+        if !config.null_values.is_empty() {
+            Self::apply_null_values(&mut bottom_data, time_col, &config.null_values, false)?;
+            Self::apply_null_values(&mut bottom_data, value_col, &config.null_values, true)?;
+        }
+
+        // Integer time values (e.g., years read from CSV) and dates are
+        // stored as strings, so every frame derived from the data has the
+        // same time dtype whatever the source. Datetimes are truncated to
+        // their day, written as daily periods ("2024-01-02")
+        let dtype = bottom_data.column(time_col)?.dtype().clone();
+        if dtype.is_integer() || matches!(dtype, DataType::Date | DataType::Datetime(..)) {
+            let mut times = bottom_data.column(time_col)?.clone();
+            if dtype.is_temporal() {
+                times = times.cast(&DataType::Date)?;
+            }
+            let times = times.cast(&DataType::String)?;
+            bottom_data.replace(time_col, times.take_materialized_series())?;
+        }
+
+        // Handle null/blank time values before parsing
+        let blank = Self::blank_periods(bottom_data.column(time_col)?)?;
+        if let Some(row) = blank.iter().position(|&b| b) {
+            match config.na_period {
+                NaPeriodPolicy::Error => {
+                    return Err(HtsError::InvalidPeriod(format!(
+                        "Empty value in time column '{time_col}' at row {row} \
+                         (use NaPeriodPolicy::Drop to skip such rows)"
+                    )));
+                }
+                NaPeriodPolicy::Drop => {
+                    let keep: BooleanChunked = blank.iter().map(|&b| !b).collect();
+                    bottom_data = bottom_data.filter(&keep)?;
+                }
+                NaPeriodPolicy::Keep => {
+                    // Blank strings become null, so the kept rows share one
+                    // key however their time cell was written
+                    let times = bottom_data.column(time_col)?;
+                    if times.dtype() == &DataType::String {
+                        let times: StringChunked = times
+                            .str()?
+                            .into_iter()
+                            .zip(&blank)
+                            .map(|(t, &b)| if b { None } else { t })
+                            .collect();
+                        bottom_data.replace(time_col, times.with_name(time_col.into()))?;
+                    }
+                }
+            }
+        }
+
+        if let Some(agg) = config.pre_aggregate {
+            let mut keys: Vec<Expr> = spec.all_columns().into_iter().map(col).collect();
+            keys.push(col(time_col));
+
+            // Non-additive values are collapsed to their weighted mean, and
+            // the weights of their observed rows summed, so that later
+            // weighted aggregation gives the same result as on the raw rows
+            let aggs = match config.weight_col.as_deref() {
+                Some(weight_col) if !config.value_semantics.is_additive() => {
+                    let weight = col(weight_col)
+                        .cast(DataType::Float64)
+                        .filter(col(value_col).is_not_null())
+                        .sum();
+                    vec![
+                        when(weight.clone().gt(lit(0.0)))
+                            .then(Self::weighted_mean(value_col, weight_col))
+                            .otherwise(lit(NULL))
+                            .alias(value_col),
+                        weight.alias(weight_col),
+                    ]
+                }
+                weight_col => std::iter::once(agg.expr(value_col).alias(value_col))
+                    .chain(weight_col.map(|w| col(w).sum()))
+                    .collect(),
+            };
+            bottom_data = bottom_data
+                .lazy()
+                .group_by_stable(keys)
+                .agg(aggs)
+                .collect()?;
+        }
 
         // Parse time periods
+        let start = Instant::now();
         let time_series = bottom_data.column(time_col)?;
-        let periods = Self::parse_periods(time_series)?;
+        let periods = Self::parse_periods(time_series, &config)?;
+        let parse_periods = start.elapsed();
 
         // Build hierarchy tree
-        let tree = HierarchyTree::from_dataframe(&bottom_data, &spec)?;
+        let start = Instant::now();
+        let tree =
+            HierarchyTree::from_dataframe_with_collation(&bottom_data, &spec, config.collation)?;
+        let build_tree = start.elapsed();
 
         // Build summation matrix
+        let start = Instant::now();
         let s_matrix = SummationMatrix::from_hierarchy(&tree);
+        let build_s_matrix = start.elapsed();
 
-        Ok(Self {
+        let diagnostics = BuildDiagnostics {
+            parse_periods,
+            build_tree,
+            build_s_matrix,
+            n_rows: bottom_data.height(),
+            n_nodes: tree.len(),
+            n_edges: 0,
+        };
+        let pool = config
+            .n_threads
+            .map(|n| {
+                ThreadPoolBuilder::new()
+                    .num_threads(n)
+                    .thread_name(|i| format!("hts-{i}"))
+                    .build()
+                    .map(Arc::new)
+            })
+            .transpose()
+            .map_err(std::io::Error::other)?;
+        let hts = Self {
             bottom_data,
             spec,
             tree,
@@ -122,7 +525,86 @@ impl HierarchicalTimeSeries {
             periods,
             time_col: time_col.to_string(),
             value_col: value_col.to_string(),
-        })
+            config,
+            pool,
+        };
+        Ok((hts, diagnostics))
+    }
+
+    /// Rebuilds a dataset from a fully aggregated frame, such as the output
+    /// of [`Self::aggregate_all`] or the long format of other tools.
+    ///
+    /// Rows where any grouping column equals `placeholder` are aggregates;
+    /// the remaining rows form the bottom level, from which the dataset is
+    /// built as in [`Self::new`]. Every aggregate row is then checked against
+    /// the sum of its bottom-level series, so only coherent frames are
+    /// accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Hierarchy`] if no row is at the bottom level,
+    /// [`HtsError::Reconciliation`] if an aggregate row does not match the
+    /// sum of the bottom level or names a series the bottom level does not
+    /// produce, and the errors of [`Self::new`].
+    pub fn from_aggregated(
+        df: DataFrame,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        placeholder: &str,
+    ) -> Result<Self> {
+        spec.validate(&df)?;
+        let is_placeholder = |name: &str| col(name).cast(DataType::String).eq(lit(placeholder));
+        let any_placeholder = spec
+            .all_columns()
+            .into_iter()
+            .map(is_placeholder)
+            .reduce(|a, b| a.or(b))
+            .unwrap_or(lit(false));
+
+        let bottom = df
+            .clone()
+            .lazy()
+            .filter(any_placeholder.clone().not())
+            .collect()?;
+        if bottom.height() == 0 {
+            return Err(HtsError::Hierarchy(format!(
+                "Every row has '{placeholder}' in a grouping column; no bottom level to rebuild"
+            )));
+        }
+        let hts = Self::new(bottom, spec, time_col, value_col)?;
+
+        // Check the aggregate rows, with the placeholder in this crate's form
+        let mut aggregates = df.lazy().filter(any_placeholder);
+        if placeholder != AGGREGATED_LABEL {
+            for name in hts.spec.all_columns() {
+                aggregates = aggregates.with_column(
+                    when(is_placeholder(name))
+                        .then(lit(AGGREGATED_LABEL))
+                        .otherwise(col(name).cast(DataType::String))
+                        .alias(name),
+                );
+            }
+        }
+        let report = hts.reconcile_external(&aggregates.collect()?, Tolerance::default())?;
+        if let Some((id, period)) = report.unmatched.first() {
+            return Err(HtsError::Reconciliation(format!(
+                "Aggregated series '{id}' in period '{period}' has no bottom-level series"
+            )));
+        }
+        if let Some(diff) = report.mismatches.first() {
+            return Err(HtsError::Reconciliation(format!(
+                "{} aggregated values do not match the sum of the bottom level; e.g., \
+                 '{}' in period '{}' is {:?}, expected {}",
+                report.mismatches.len(),
+                diff.id,
+                diff.period,
+                diff.actual,
+                diff.expected
+            )));
+        }
+
+        Ok(hts)
     }
 
     /// Loads hierarchical time series data from a CSV file.
@@ -143,202 +625,3780 @@ impl HierarchicalTimeSeries {
         time_col: &str,
         value_col: &str,
     ) -> Result<Self> {
-        let df = CsvReadOptions::default()
-            .with_has_header(true)
-            .try_into_reader_with_file_path(Some(path.as_ref().into()))?
-            .finish()?;
-
-        Self::new(df, spec, time_col, value_col)
+        Self::from_csv_with_config(path, spec, time_col, value_col, HtsConfig::default())
     }
 
-    /// Parses time periods from a Series.
-    fn parse_periods(series: &Column) -> Result<Vec<Period>> {
-        let mut periods = Vec::new();
-        let mut seen = std::collections::HashSet::new();
-
-        for i in 0..series.len() {
-            let val = series.get(i)?;
-            let s = val.to_string();
-            let s = s.trim_matches('"');
+    /// Loads hierarchical time series data from a CSV file with custom options.
+    ///
+    /// Spreadsheet exports frequently contain trailing rows with an empty
+    /// time cell; pass [`NaPeriodPolicy::Drop`] to skip them.
+    ///
+    /// The file is memory-mapped unless [`HtsConfig::csv`] disables it; see
+    /// [`CsvImportOptions`](crate::CsvImportOptions).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_csv_with_config<P: AsRef<Path>>(
+        path: P,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: HtsConfig,
+    ) -> Result<Self> {
+        let df = Self::read_csv(path.as_ref(), config.csv.memory_map)?;
+        Self::with_config(df, spec, time_col, value_col, config)
+    }
 
-            if !seen.contains(s) {
-                let period = Period::parse(s)?;
-                periods.push(period);
-                seen.insert(s.to_string());
+    /// Reads a CSV file with a header row, memory-mapped when `memory_map` is
+    /// set and the file allows it, otherwise from an in-memory copy.
+    fn read_csv(path: &Path, memory_map: bool) -> Result<DataFrame> {
+        let options = CsvReadOptions::default().with_has_header(true);
+        if memory_map {
+            match options
+                .clone()
+                .try_into_reader_with_file_path(Some(path.into()))
+                .and_then(|reader| reader.finish())
+            {
+                // Mapping can fail where reading works (e.g. some network
+                // filesystems); any genuine I/O problem resurfaces below
+                Err(PolarsError::IO { .. }) => {}
+                result => return Ok(result?),
             }
         }
 
-        periods.sort();
-        Ok(periods)
+        let bytes = std::fs::read(path)?;
+        Ok(options
+            .into_reader_with_file_handle(std::io::Cursor::new(bytes))
+            .finish()?)
     }
 
-    /// Returns the total number of series (all aggregation levels).
-    pub fn n_series(&self) -> usize {
-        self.tree.n_series()
+    /// Loads hierarchical time series data from several CSV files as one dataset.
+    ///
+    /// All files matching the glob `pattern` (e.g., `"data/trips_*.csv"`) are
+    /// scanned and concatenated, in path order, into a single bottom-level
+    /// frame. Useful when data is partitioned into files by year or region.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is invalid or matches no file, if the
+    /// files do not all have the same columns, or if any file cannot be read.
+    pub fn from_csv_glob(
+        pattern: &str,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+    ) -> Result<Self> {
+        Self::from_csv_glob_with_value_candidates(pattern, spec, time_col, value_col, &[])
     }
 
-    /// Returns the number of bottom-level series.
-    pub fn n_bottom(&self) -> usize {
-        self.tree.n_bottom()
-    }
+    /// Loads several CSV files like [`Self::from_csv_glob`], accepting a
+    /// differently named value column in each file.
+    ///
+    /// In every file, the first of `candidates` present among its columns is
+    /// renamed to `value_col`, so sources that call the same measure "Trips",
+    /// "Value" or "Count" can be combined. A file that already has
+    /// `value_col` is left as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::ColumnNotFound`] naming the file if it has neither
+    /// `value_col` nor any of the candidates, plus the errors of
+    /// [`Self::from_csv_glob`].
+    pub fn from_csv_glob_with_value_candidates(
+        pattern: &str,
+        spec: HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        candidates: &[&str],
+    ) -> Result<Self> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
 
-    /// Returns the number of time periods.
-    pub fn n_periods(&self) -> usize {
-        self.periods.len()
-    }
+        let mut paths = Vec::new();
+        for entry in glob::glob(pattern).map_err(|e| invalid(e.to_string()))? {
+            paths.push(entry.map_err(std::io::Error::from)?);
+        }
+        if paths.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No file matches '{pattern}'"),
+            )
+            .into());
+        }
 
-    /// Returns the time periods.
-    pub fn periods(&self) -> &[Period] {
-        &self.periods
-    }
+        let mut scans = Vec::with_capacity(paths.len());
+        let mut first_names: Option<Vec<String>> = None;
+        for path in &paths {
+            let mut scan = LazyCsvReader::new(path).with_has_header(true).finish()?;
+            let schema = scan.collect_schema()?;
 
-    /// Returns the summation matrix.
-    pub fn summation_matrix(&self) -> &SummationMatrix {
-        &self.s_matrix
-    }
+            if !candidates.is_empty() && !schema.contains(value_col) {
+                let Some(&found) = candidates.iter().find(|&&c| schema.contains(c)) else {
+                    return Err(HtsError::ColumnNotFound(format!(
+                        "'{}' has no value column among {value_col:?} and {candidates:?}",
+                        path.display()
+                    )));
+                };
+                scan = scan.rename([found], [value_col], true);
+            }
 
-    /// Returns the hierarchy tree.
-    pub fn hierarchy_tree(&self) -> &HierarchyTree {
-        &self.tree
-    }
+            let names: Vec<String> = scan
+                .collect_schema()?
+                .iter_names()
+                .map(|name| name.to_string())
+                .collect();
 
-    /// Returns the hierarchy specification.
-    pub fn spec(&self) -> &HierarchySpec {
-        &self.spec
-    }
+            match &first_names {
+                None => first_names = Some(names),
+                Some(first) if *first != names => {
+                    return Err(HtsError::SchemaMismatch(format!(
+                        "'{}' has columns {names:?}, but '{}' has {first:?}",
+                        path.display(),
+                        paths[0].display()
+                    )));
+                }
+                Some(_) => {}
+            }
+            scans.push(scan);
+        }
 
-    /// Returns the bottom-level data.
-    pub fn bottom_data(&self) -> &DataFrame {
-        &self.bottom_data
+        // Numeric columns may be inferred as integer in some files and float
+        // in others, so let Polars find a common type.
+        let args = UnionArgs {
+            to_supertypes: true,
+            ..Default::default()
+        };
+        let df = concat(scans, args)?.collect()?;
+
+        Self::new(df, spec, time_col, value_col)
     }
 
-    /// Aggregates the data to create a DataFrame with all levels.
+    /// Returns a copy of this dataset with `column` appended to the grouping
+    /// columns.
     ///
-    /// Returns a DataFrame with columns for each grouping key, time, and value,
-    /// containing data for all aggregation levels.
-    /// Aggregates the data to create a DataFrame with all levels.
+    /// The column must already be present in the bottom-level data. The
+    /// hierarchy tree and summation matrix are rebuilt, while the data and
+    /// parsed periods are reused as is.
     ///
-    /// Returns a DataFrame with columns for each grouping key, time, and value,
-    /// containing data for all aggregation levels. Missing columns at each level
-    /// are filled with "<aggregated>".
-    pub fn aggregate_all(&self) -> Result<DataFrame> {
-        let all_cols = self.spec.all_columns();
-        // Get all combinations of columns that define the levels
-        let levels = self.spec.level_combinations();
-
-        // We will collect lazy frames for each level and concat them
-        let mut frames = Vec::new();
-
-        for level_cols in levels {
-            // Group by the current level columns + time
-            let mut group_cols: Vec<Expr> = level_cols.iter().map(|c| col(c.as_str())).collect();
-            group_cols.push(col(&self.time_col));
+    /// # Errors
+    ///
+    /// Returns an error if `column` is absent from the bottom-level data, or
+    /// if it is already part of the spec or is the time or value column.
+    pub fn with_added_group(&self, column: &str) -> Result<Self> {
+        if self.bottom_data.column(column).is_err() {
+            return Err(HtsError::ColumnNotFound(column.to_string()));
+        }
+        if self.spec.all_columns().contains(&column)
+            || column == self.time_col
+            || column == self.value_col
+        {
+            return Err(HtsError::Hierarchy(format!(
+                "Column '{column}' is already in use"
+            )));
+        }
 
-            let mut lf = self
-                .bottom_data
-                .clone()
-                .lazy()
-                .group_by(group_cols)
-                .agg([col(&self.value_col).sum()]);
+        let mut spec = self.spec.clone();
+        spec.groups.push(column.to_string());
+        let mut tree = HierarchyTree::from_dataframe_with_collation(
+            &self.bottom_data,
+            &spec,
+            self.config.collation,
+        )?;
+        self.carry_meta(&mut tree, |node| Some(spec.series_id(&node.labels)));
+        let s_matrix = SummationMatrix::from_hierarchy(&tree);
 
-            // Add missing columns as literals "<aggregated>"
-            for &col_name in &all_cols {
-                if !level_cols.contains(&col_name.to_string()) {
-                    lf = lf.with_column(lit("<aggregated>").alias(col_name));
-                }
-            }
+        Ok(Self {
+            bottom_data: self.bottom_data.clone(),
+            spec,
+            tree,
+            s_matrix,
+            periods: self.periods.clone(),
+            time_col: self.time_col.clone(),
+            value_col: self.value_col.clone(),
+            config: self.config.clone(),
+            pool: self.pool.clone(),
+        })
+    }
 
-            // Select columns in consistent order
-            let mut select_cols: Vec<Expr> = all_cols.iter().map(|c| col(*c)).collect();
-            select_cols.push(col(&self.time_col));
-            select_cols.push(col(&self.value_col));
+    /// Returns the sub-hierarchy under one node, e.g. a single region for a
+    /// drill-down view or to be reconciled on its own.
+    ///
+    /// The bottom-level data is filtered to the rows under `root_id`, and the
+    /// hierarchy tree and summation matrix are rebuilt from them with the
+    /// same spec, so series keep their ids. The rebuilt total therefore
+    /// equals the `root_id` series, and each level above it holds a single
+    /// node. The parsed periods are reused as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Hierarchy`] if `root_id` names no node.
+    pub fn subtree(&self, root_id: &str) -> Result<Self> {
+        let node = self
+            .tree
+            .get_node(root_id)
+            .ok_or_else(|| HtsError::Hierarchy(format!("Unknown series '{root_id}'")))?;
 
-            frames.push(lf.select(select_cols));
+        let mut lf = self.bottom_data.clone().lazy();
+        for (column, label) in &node.labels {
+            lf = lf.filter(
+                col(column.as_str())
+                    .cast(DataType::String)
+                    .eq(lit(label.as_str())),
+            );
         }
+        let bottom_data = lf.collect()?;
 
-        // Concat all levels
-        let concatenated = concat(frames, UnionArgs::default())?;
+        let mut tree = HierarchyTree::from_dataframe_with_collation(
+            &bottom_data,
+            &self.spec,
+            self.config.collation,
+        )?;
+        // The rebuilt total only covers the subtree, so the original total's
+        // attributes no longer describe it
+        let keep_total = node.is_total();
+        self.carry_meta(&mut tree, |old| {
+            (keep_total || !old.is_total()).then(|| old.id.clone())
+        });
+        let s_matrix = SummationMatrix::from_hierarchy(&tree);
 
-        // Collect into DataFrame
-        let df = concatenated.collect()?;
-        Ok(df)
+        Ok(Self {
+            bottom_data,
+            spec: self.spec.clone(),
+            tree,
+            s_matrix,
+            periods: self.periods.clone(),
+            time_col: self.time_col.clone(),
+            value_col: self.value_col.clone(),
+            config: self.config.clone(),
+            pool: self.pool.clone(),
+        })
     }
 
-    /// Gets the values for a specific series across all time periods.
+    /// Renames labels of a hierarchy or group column in place, e.g. to fix a
+    /// typo in a category name.
     ///
-    /// # Arguments
+    /// Values of `column` found in `mapping` are replaced, others are kept;
+    /// the column is converted to strings. The hierarchy tree and summation
+    /// matrix are then rebuilt, while the parsed periods are reused.
     ///
-    /// * `series_id` - The series identifier (e.g., "South Australia/Adelaide/Business")
+    /// Mapping two labels to the same new label may merge distinct bottom
+    /// series into one, changing the structure. That is rejected unless
+    /// `allow_merge` is set, in which case the merged series' rows are simply
+    /// summed by later aggregations.
     ///
-    /// # Returns
+    /// # Errors
+    ///
+    /// Returns [`HtsError::ColumnNotFound`] if `column` is not a hierarchy or
+    /// group column, or [`HtsError::Hierarchy`] listing the merged series if
+    /// the relabeling merges bottom series and `allow_merge` is `false`. On
+    /// error, `self` is left unchanged.
+    pub fn relabel(
+        &mut self,
+        column: &str,
+        mapping: &HashMap<String, String>,
+        allow_merge: bool,
+    ) -> Result<()> {
+        if !self.spec.all_columns().contains(&column) {
+            return Err(HtsError::ColumnNotFound(column.to_string()));
+        }
+
+        if !allow_merge {
+            let mut merged: HashMap<String, Vec<&str>> = HashMap::new();
+            for id in self.s_matrix.col_labels() {
+                let node = self.tree.get_node(id).expect("S columns are tree nodes");
+                let mut labels = node.labels.clone();
+                if let Some(label) = labels.get_mut(column)
+                    && let Some(new) = mapping.get(label.as_str())
+                {
+                    *label = new.clone();
+                }
+                merged
+                    .entry(self.spec.series_id(&labels))
+                    .or_default()
+                    .push(id);
+            }
+
+            let mut collisions: Vec<String> = merged
+                .into_iter()
+                .filter(|(_, ids)| ids.len() > 1)
+                .map(|(new, ids)| format!("{} -> {new}", ids.join(", ")))
+                .collect();
+            if !collisions.is_empty() {
+                collisions.sort();
+                return Err(HtsError::Hierarchy(format!(
+                    "Relabeling '{column}' merges bottom-level series: {}",
+                    collisions.join("; ")
+                )));
+            }
+        }
+
+        let values = self.bottom_data.column(column)?.cast(&DataType::String)?;
+        let relabeled: StringChunked = values
+            .str()?
+            .into_iter()
+            .map(|v| v.map(|v| mapping.get(v).map_or(v, String::as_str)))
+            .collect();
+
+        let mut bottom_data = self.bottom_data.clone();
+        bottom_data.replace(column, relabeled.with_name(column.into()).into_series())?;
+        let mut tree = HierarchyTree::from_dataframe_with_collation(
+            &bottom_data,
+            &self.spec,
+            self.config.collation,
+        )?;
+        self.carry_meta(&mut tree, |node| {
+            let mut labels = node.labels.clone();
+            if let Some(label) = labels.get_mut(column)
+                && let Some(new) = mapping.get(label.as_str())
+            {
+                *label = new.clone();
+            }
+            Some(self.spec.series_id(&labels))
+        });
+        let s_matrix = SummationMatrix::from_hierarchy(&tree);
+
+        self.bottom_data = bottom_data;
+        self.tree = tree;
+        self.s_matrix = s_matrix;
+        Ok(())
+    }
+
+    /// Copies node metadata into a rebuilt `tree`, mapping each node to its
+    /// id in the new tree with `new_id` (`None` drops its metadata).
+    fn carry_meta(&self, tree: &mut HierarchyTree, new_id: impl Fn(&Node) -> Option<String>) {
+        for node in self.tree.nodes() {
+            if node.meta.is_empty() {
+                continue;
+            }
+            let Some(id) = new_id(node) else {
+                continue;
+            };
+            for (key, value) in &node.meta {
+                tree.set_node_meta(&id, key.clone(), value.clone());
+            }
+        }
+    }
+
+    /// Replaces the `tokens` in a string column by nulls, then casts it to
+    /// `f64` if `numeric` is set. Non-string columns are left as is.
+    fn apply_null_values(
+        df: &mut DataFrame,
+        name: &str,
+        tokens: &[String],
+        numeric: bool,
+    ) -> Result<()> {
+        let column = df.column(name)?;
+        let Ok(values) = column.str() else {
+            return Ok(());
+        };
+
+        let cleaned: StringChunked = values
+            .into_iter()
+            .map(|v| v.filter(|v| !tokens.iter().any(|t| t == v.trim())))
+            .collect();
+        let mut cleaned = cleaned.with_name(name.into()).into_series();
+        if numeric {
+            cleaned = cleaned.strict_cast(&DataType::Float64)?;
+        }
+
+        df.replace(name, cleaned)?;
+        Ok(())
+    }
+
+    /// Flags null or blank values in the time column.
+    fn blank_periods(series: &Column) -> Result<Vec<bool>> {
+        let mut blank = Vec::with_capacity(series.len());
+
+        for i in 0..series.len() {
+            let val = series.get(i)?;
+            let is_blank = match val {
+                AnyValue::Null => true,
+                _ => val.to_string().trim_matches('"').trim().is_empty(),
+            };
+            blank.push(is_blank);
+        }
+
+        Ok(blank)
+    }
+
+    /// Parses time periods from a Series.
+    ///
+    /// Null and blank values are skipped; callers decide how to handle them
+    /// beforehand according to the configured [`NaPeriodPolicy`]. Distinct
+    /// periods are sorted if `config.sort_periods` is set.
+    ///
+    /// Date and datetime columns map directly to [`Period::Daily`] without
+    /// formatting; other columns are read as strings in a single pass,
+    /// parsing each distinct value once.
+    fn parse_periods(series: &Column, config: &HtsConfig) -> Result<Vec<Period>> {
+        let mut periods = Vec::new();
+
+        let strings = series.cast(&DataType::String)?;
+        let mut seen = HashSet::new();
+        for (i, s) in strings.str()?.into_iter().enumerate() {
+            let Some(s) = s.filter(|s| !s.trim().is_empty()) else {
+                continue;
+            };
+            if !seen.insert(s) {
+                continue;
+            }
+
+            let period = Period::parse_as(s, config.period_format)
+                .map_err(|e| Self::locate_period_error(e, i, series.name()))?;
+            periods.push(period);
+        }
+
+        if config.sort_periods {
+            periods.sort();
+        }
+        Ok(periods)
+    }
+
+    /// Returns the total number of series (all aggregation levels).
+    pub fn n_series(&self) -> usize {
+        self.tree.n_series()
+    }
+
+    /// Returns the number of bottom-level series.
+    pub fn n_bottom(&self) -> usize {
+        self.tree.n_bottom()
+    }
+
+    /// Returns the number of time periods.
+    pub fn n_periods(&self) -> usize {
+        self.periods.len()
+    }
+
+    /// Returns the distinct time periods.
+    ///
+    /// Periods are sorted chronologically unless `HtsConfig::sort_periods` is
+    /// `false`, in which case they are in first-seen order. Code that needs
+    /// chronological order should not assume it here and sort locally.
+    pub fn periods(&self) -> &[Period] {
+        &self.periods
+    }
+
+    /// Returns the summation matrix.
+    pub fn summation_matrix(&self) -> &SummationMatrix {
+        &self.s_matrix
+    }
+
+    /// Returns the hierarchy tree.
+    pub fn hierarchy_tree(&self) -> &HierarchyTree {
+        &self.tree
+    }
+
+    /// Attaches the attribute `key` = `value` to the node `id` of this
+    /// dataset's tree, as [`HierarchyTree::set_node_meta`] does, and returns
+    /// whether the node exists.
+    ///
+    /// Attributes survive [`Self::relabel`], [`Self::with_added_group`] and
+    /// [`Self::subtree`], following nodes whose ids change.
+    pub fn set_node_meta(
+        &mut self,
+        id: &str,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> bool {
+        self.tree.set_node_meta(id, key, value)
+    }
+
+    /// Returns a lightweight view of the structure for reconciliation.
+    ///
+    /// The context borrows the summation matrix and carries each row's level,
+    /// but none of the bottom-level data.
+    pub fn recon_context(&self) -> ReconContext<'_> {
+        let levels = self.tree.nodes().iter().map(|n| n.level).collect();
+        ReconContext::new(&self.s_matrix, levels).expect("tree and S have the same rows")
+    }
+
+    /// Returns the hierarchy specification.
+    pub fn spec(&self) -> &HierarchySpec {
+        &self.spec
+    }
+
+    /// Returns the bottom-level data.
+    pub fn bottom_data(&self) -> &DataFrame {
+        &self.bottom_data
+    }
+
+    /// Returns the bottom-level data in the long layout of
+    /// [`Self::aggregate_all`]: the grouping columns in
+    /// [`HierarchySpec::all_columns`] order, then the time and value columns.
+    ///
+    /// Rows keep the order of [`Self::bottom_data`]; any other columns, such
+    /// as a [weight column](HtsConfig::weight_col), are dropped. Passing the
+    /// result back to [`Self::new`] with the same spec rebuilds an equivalent
+    /// dataset, whatever layout the data was first loaded from.
+    pub fn to_long(&self) -> DataFrame {
+        let columns = self
+            .spec
+            .all_columns()
+            .into_iter()
+            .chain([self.time_col.as_str(), self.value_col.as_str()]);
+        self.bottom_data
+            .select(columns)
+            .expect("columns are validated at construction")
+    }
+
+    /// Returns the bottom-level values as `f64`, whatever the stored type of
+    /// the value column.
+    ///
+    /// Integer columns (e.g., counts read from CSV) are cast, so callers need
+    /// not match on the dtype before calling `.f64()`. Null values stay null.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value column cannot be cast to `f64`, e.g.
+    /// because it holds text.
+    pub fn values_f64(&self) -> Result<Float64Chunked> {
+        let values = self
+            .bottom_data
+            .column(&self.value_col)?
+            .strict_cast(&DataType::Float64)?;
+        Ok(values.f64()?.clone())
+    }
+
+    /// Returns the options used to build this dataset.
+    pub fn config(&self) -> &HtsConfig {
+        &self.config
+    }
+
+    /// Aggregates the data to create a DataFrame with all levels.
+    ///
+    /// Returns a DataFrame with columns for each grouping key, time, and value,
+    /// containing data for all aggregation levels. Missing columns at each level
+    /// are filled with "<aggregated>".
+    ///
+    /// Levels appear in `level_combinations` order. Within a level, rows follow
+    /// the order in which each (key, period) combination first appears in the
+    /// bottom-level data, so the output is deterministic.
+    ///
+    /// Rows kept without a time value by [`NaPeriodPolicy::Keep`] belong to
+    /// no period and are left out of every aggregate; they still shape the
+    /// hierarchy.
+    ///
+    /// # Parallelism
+    ///
+    /// The per-level group-bys are independent and are executed concurrently on
+    /// Polars' thread pool, as is each group-by itself. Set the
+    /// `POLARS_MAX_THREADS` environment variable before first use to bound it,
+    /// and [`HtsConfig::parallel`] to `false` (or [`HtsConfig::n_threads`] to
+    /// `Some(1)`) to run the levels one at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::MissingData`] in [strict mode](HtsConfig::strict)
+    /// if any bottom-level cell has no observation, and
+    /// [`HtsError::NonAdditive`] if the values are not additive and no
+    /// [weight column](HtsConfig::weight_col) is set.
+    ///
+    /// # Non-additive values
+    ///
+    /// When [`HtsConfig::value_semantics`] is not
+    /// [`ValueKind::Additive`](crate::ValueKind::Additive),
+    /// each value is the weighted mean of its bottom-level values instead of
+    /// their sum, using [`HtsConfig::weight_col`] as weights. Weights are
+    /// taken per period, so they may vary over time. Such aggregates are not
+    /// sums of the bottom level: the summation matrix does not relate them,
+    /// and the reconciliation methods refuse them.
+    pub fn aggregate_all(&self) -> Result<DataFrame> {
+        self.check_strict()?;
+
+        let frames = Self::level_frames(
+            self.dated_rows(),
+            &self.spec,
+            &self.time_col,
+            &self.value_col,
+            self.value_agg()?,
+            false,
+        );
+        let lf = Self::union_levels(frames, &self.config)?;
+
+        // Collect into DataFrame
+        let df = self.install(|| lf.collect())?;
+        Ok(df)
+    }
+
+    /// Aggregates the data like [`Self::aggregate_all`], keeping only the
+    /// given levels.
+    ///
+    /// `levels` are indices into [`HierarchySpec::level_combinations`]; the
+    /// output lists them in that order whatever the order of `levels`, and
+    /// unlisted levels are never computed. Use [`Self::aggregate_all`] for
+    /// every level.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Hierarchy`] if an index is not below the number of
+    /// levels, and the errors of [`Self::aggregate_all`].
+    pub fn aggregate_levels(&self, levels: &[usize]) -> Result<DataFrame> {
+        let n_levels = self.tree.n_levels();
+        if let Some(&bad) = levels.iter().find(|&&l| l >= n_levels) {
+            return Err(HtsError::Hierarchy(format!(
+                "Level {bad} does not exist; the hierarchy has {n_levels} levels"
+            )));
+        }
+        self.check_strict()?;
+
+        let frames = Self::level_frames(
+            self.dated_rows(),
+            &self.spec,
+            &self.time_col,
+            &self.value_col,
+            self.value_agg()?,
+            false,
+        )
+        .into_iter()
+        .enumerate()
+        .filter(|(level, _)| levels.contains(level))
+        .map(|(_, lf)| lf)
+        .collect();
+        let lf = Self::union_levels(frames, &self.config)?;
+        let df = self.install(|| lf.collect())?;
+        Ok(df)
+    }
+
+    /// Runs `op` on the thread pool of [`HtsConfig::n_threads`], or on the
+    /// calling thread's pool if none was set.
+    fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Returns the bottom-level rows that have a time value.
+    ///
+    /// Under [`NaPeriodPolicy::Keep`], `build` leaves the kept rows with a
+    /// null time; they are filtered out here rather than grouped as a
+    /// period of their own.
+    fn dated_rows(&self) -> LazyFrame {
+        let lf = self.bottom_data.clone().lazy();
+        match self.config.na_period {
+            NaPeriodPolicy::Keep => lf.filter(col(self.time_col.as_str()).is_not_null()),
+            NaPeriodPolicy::Error | NaPeriodPolicy::Drop => lf,
+        }
+    }
+
+    /// Sums the bottom-level data to the grand total of each period.
+    ///
+    /// A single group-by on the time column, skipping the per-level work of
+    /// [`Self::aggregate_all`]; handy when the total is all that is needed.
+    /// Periods follow [`Self::periods`] order, and rows without a time value
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::NonAdditive`] for non-additive values without a
+    /// [weight column](HtsConfig::weight_col). With one, the totals are
+    /// weighted means, as in [`Self::aggregate_all`].
+    pub fn total_by_period(&self) -> Result<Vec<(Period, f64)>> {
+        let totals = self.node_by_period(&HashMap::new())?;
+        Ok(self.periods.iter().copied().zip(totals).collect())
+    }
+
+    /// Reduces the bottom-level rows matching `labels` (all rows if empty)
+    /// to one value per period, in [`Self::periods`] order. Periods without
+    /// any such row are zero.
+    fn node_by_period(&self, labels: &HashMap<String, String>) -> Result<Vec<f64>> {
+        let agg = self
+            .value_agg()?
+            .unwrap_or_else(|| col(self.value_col.as_str()).cast(DataType::Float64).sum());
+        let mut lf = self.bottom_data.clone().lazy();
+        for (column, label) in labels {
+            lf = lf.filter(
+                col(column.as_str())
+                    .cast(DataType::String)
+                    .eq(lit(label.as_str())),
+            );
+        }
+        let totals = lf
+            .group_by([col(self.time_col.as_str())])
+            .agg([agg.alias(self.value_col.as_str())])
+            .collect()?;
+
+        let times = totals.column(&self.time_col)?.cast(&DataType::String)?;
+        let values = totals.column(&self.value_col)?.f64()?.clone();
+        let mut by_period = HashMap::with_capacity(totals.height());
+        for (time, value) in times.str()?.into_iter().zip(&values) {
+            let Some(time) = time.filter(|t| !t.trim().is_empty()) else {
+                continue;
+            };
+            let period = Period::parse_as(time, self.config.period_format)?;
+            *by_period.entry(period).or_insert(0.0) += value.unwrap_or_default();
+        }
+
+        Ok(self
+            .periods
+            .iter()
+            .map(|p| by_period.get(p).copied().unwrap_or_default())
+            .collect())
+    }
+
+    /// Writes the output of [`Self::aggregate_all`] as CSV.
+    ///
+    /// With `precision`, floats are written with exactly that many decimal
+    /// places, e.g. to match a reference file byte for byte; `None` keeps
+    /// Polars' native formatting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if aggregation or writing fails.
+    pub fn write_aggregated_csv<W: Write>(
+        &self,
+        writer: W,
+        precision: Option<usize>,
+    ) -> Result<()> {
+        let mut df = self.aggregate_all()?;
+        CsvWriter::new(writer)
+            .include_header(true)
+            .with_float_precision(precision)
+            .finish(&mut df)?;
+        Ok(())
+    }
+
+    /// Writes the output of [`Self::aggregate_all`] as an Arrow IPC stream.
+    ///
+    /// Arrow consumers such as `pyarrow` can read the stream without parsing
+    /// text, keeping the column types intact. Requires the `ipc` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if aggregation or writing fails.
+    #[cfg(feature = "ipc")]
+    pub fn aggregate_to_ipc<W: Write>(&self, w: W) -> Result<()> {
+        let mut df = self.aggregate_all()?;
+        IpcStreamWriter::new(w).finish(&mut df)?;
+        Ok(())
+    }
+
+    /// Aggregates the data like [`Self::aggregate_all`], adding a `count`
+    /// column.
+    ///
+    /// The count is the number of non-null bottom-level observations summed
+    /// into each value, which tells a sum built from one observation apart
+    /// from one built from many.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::aggregate_all`].
+    pub fn aggregate_all_with_counts(&self) -> Result<DataFrame> {
+        self.check_strict()?;
+
+        let frames = Self::level_frames(
+            self.dated_rows(),
+            &self.spec,
+            &self.time_col,
+            &self.value_col,
+            self.value_agg()?,
+            true,
+        );
+        let lf = Self::union_levels(frames, &self.config)?;
+
+        let df = self.install(|| lf.collect())?;
+        Ok(df)
+    }
+
+    /// Aggregates the data like [`Self::aggregate_all`], adding an
+    /// `s_row_index` column.
+    ///
+    /// The index is the row of the summation matrix holding each output row's
+    /// series, so values can be moved between the frame and matrix operations
+    /// without joining on labels.
+    pub fn aggregate_all_with_row_index(&self) -> Result<DataFrame> {
+        let mut df = self.aggregate_all()?;
+
+        let row_index: HashMap<&str, u32> = self
+            .s_matrix
+            .row_labels()
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i as u32))
+            .collect();
+
+        let mut indices = Vec::with_capacity(df.height());
+        for id in self.aggregated_ids(&df)? {
+            let &index = row_index.get(id.as_str()).ok_or_else(|| {
+                HtsError::Hierarchy(format!("Aggregated series '{id}' has no row in S"))
+            })?;
+            indices.push(index);
+        }
+
+        df.with_column(Column::new("s_row_index".into(), indices))?;
+        Ok(df)
+    }
+
+    /// Composes the series id of every row of a frame in the
+    /// [`Self::aggregate_all`] layout, where [`AGGREGATED_LABEL`] marks the
+    /// columns a level does not use.
+    fn aggregated_ids(&self, df: &DataFrame) -> Result<Vec<String>> {
+        let all_cols = self.spec.all_columns();
+        let keys = all_cols
+            .iter()
+            .map(|&c| {
+                let column = df
+                    .column(c)
+                    .map_err(|_| HtsError::ColumnNotFound(c.to_string()))?;
+                Ok(column.cast(&DataType::String)?.str()?.clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let ids = (0..df.height())
+            .map(|r| {
+                let labels: HashMap<String, String> = all_cols
+                    .iter()
+                    .zip(&keys)
+                    .filter_map(|(name, key)| {
+                        let label = key.get(r).unwrap_or("null");
+                        (label != AGGREGATED_LABEL).then(|| (name.to_string(), label.to_string()))
+                    })
+                    .collect();
+                self.spec.series_id(&labels)
+            })
+            .collect();
+        Ok(ids)
+    }
+
+    /// Reads the series id, period and value of every row of a frame in the
+    /// [`Self::aggregate_all`] layout.
+    ///
+    /// Rows with a null or blank period, such as those kept by
+    /// [`NaPeriodPolicy::Keep`], belong to no period and are skipped.
+    fn frame_cells(&self, df: &DataFrame) -> Result<(Vec<String>, Vec<Period>, Float64Chunked)> {
+        let times = df
+            .column(&self.time_col)
+            .map_err(|_| HtsError::ColumnNotFound(self.time_col.clone()))?
+            .cast(&DataType::String)?;
+        let dated: BooleanChunked = times
+            .str()?
+            .into_iter()
+            .map(|t| t.is_some_and(|t| !t.trim().is_empty()))
+            .collect();
+        let df = &df.filter(&dated)?;
+        let times = times.filter(&dated)?;
+
+        let ids = self.aggregated_ids(df)?;
+        let periods = times
+            .str()?
+            .into_iter()
+            .map(|t| Period::parse_as(t.unwrap_or_default(), self.config.period_format))
+            .collect::<Result<Vec<_>>>()?;
+        let values = df
+            .column(&self.value_col)
+            .map_err(|_| HtsError::ColumnNotFound(self.value_col.clone()))?
+            .cast(&DataType::Float64)?;
+        Ok((ids, periods, values.f64()?.clone()))
+    }
+
+    /// Compares externally computed aggregates against this crate's own.
+    ///
+    /// `external` must have the [`Self::aggregate_all`] layout: the grouping
+    /// columns (with [`AGGREGATED_LABEL`] for unused ones), the time column
+    /// and the value column. Every row is matched to the recomputed
+    /// aggregate of the same series and period, and compared within `tol`.
+    /// Rows without a period are skipped, as they are in the aggregates.
+    /// Useful when blending outputs of other tools (e.g., R's `fable`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::ColumnNotFound`] if `external` lacks a grouping,
+    /// time or value column, or an error if a time value cannot be parsed.
+    pub fn reconcile_external(&self, external: &DataFrame, tol: Tolerance) -> Result<DiffReport> {
+        let (ids, periods, values) = self.frame_cells(&self.aggregate_all()?)?;
+        let expected: HashMap<(&str, Period), f64> = ids
+            .iter()
+            .zip(&periods)
+            .zip(&values)
+            .map(|((id, &period), value)| ((id.as_str(), period), value.unwrap_or(0.0)))
+            .collect();
+
+        let (ext_ids, ext_periods, ext_values) = self.frame_cells(external)?;
+        let mut report = DiffReport::default();
+        for ((id, period), actual) in ext_ids.into_iter().zip(ext_periods).zip(&ext_values) {
+            let Some(&expected) = expected.get(&(id.as_str(), period)) else {
+                report.unmatched.push((id, period.to_string()));
+                continue;
+            };
+
+            report.n_compared += 1;
+            if !actual.is_some_and(|actual| tol.approx_eq(actual, expected)) {
+                report.mismatches.push(ValueDiff {
+                    id,
+                    period: period.to_string(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Aggregates the data to all levels with a custom Polars aggregation.
+    ///
+    /// Produces the same layout as [`Self::aggregate_all`], but each value is
+    /// `agg` evaluated over the bottom-level rows of a (series, period)
+    /// group, e.g. `col("Value").mean()` or `col("Value").last()`. The result
+    /// is stored in the value column whatever name `agg` produces.
+    ///
+    /// Only sums are linear, so other reducers do not satisfy y = Sb and
+    /// their output should not be reconciled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::polars::prelude::*;
+    /// use hts_core::testing::{VALUE_COL, random_hierarchy};
+    ///
+    /// let hts = random_hierarchy(&[2, 2], &[], 4, 1);
+    /// let maxima = hts.aggregate_all_expr(col(VALUE_COL).max()).unwrap();
+    ///
+    /// assert_eq!(maxima.height(), hts.n_series() * hts.n_periods());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `agg` cannot be evaluated.
+    pub fn aggregate_all_expr(&self, agg: Expr) -> Result<DataFrame> {
+        self.check_strict()?;
+
+        let frames = Self::level_frames(
+            self.dated_rows(),
+            &self.spec,
+            &self.time_col,
+            &self.value_col,
+            Some(agg),
+            false,
+        );
+        let lf = Self::union_levels(frames, &self.config)?;
+
+        let df = self.install(|| lf.collect())?;
+        Ok(df)
+    }
+
+    /// Aggregates a lazy scan of bottom-level data to all levels, out of core.
     ///
-    /// A vector of values for each time period, or None if series not found.
-    pub fn get_series(&self, series_id: &str) -> Option<Vec<f64>> {
-        let _node = self.tree.get_node(series_id)?;
+    /// Produces the same layout as [`Self::aggregate_all`], but as a
+    /// `LazyFrame` with Polars' streaming engine enabled, so the bottom-level
+    /// data never has to be materialized in full. Sink the result to disk
+    /// (e.g., `sink_csv`) when the output does not fit in memory either.
+    ///
+    /// Use [`HierarchyTree::from_lazy`] on the same scan to obtain the tree
+    /// and summation matrix without loading the data.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hts_core::{HierarchicalTimeSeries, HierarchySpec};
+    /// use polars::prelude::*;
+    ///
+    /// let spec = HierarchySpec::new(vec!["State".into(), "Region".into()], vec!["Purpose".into()]);
+    /// let scan = LazyCsvReader::new("huge.csv").with_has_header(true).finish().unwrap();
+    ///
+    /// HierarchicalTimeSeries::aggregate_streaming(scan, &spec, "Quarter", "Trips")
+    ///     .unwrap()
+    ///     .sink_csv("aggregated.csv", Default::default(), None)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a spec, time, or value column is missing from the scan.
+    pub fn aggregate_streaming(
+        scan: LazyFrame,
+        spec: &HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+    ) -> Result<LazyFrame> {
+        Self::aggregate_streaming_with_config(
+            scan,
+            spec,
+            time_col,
+            value_col,
+            &HtsConfig::default(),
+        )
+    }
+
+    /// Aggregates a lazy scan like [`Self::aggregate_streaming`], evaluating
+    /// the levels concurrently only if [`HtsConfig::parallel`] is set. The
+    /// other options apply to constructed datasets and are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::aggregate_streaming`].
+    pub fn aggregate_streaming_with_config(
+        scan: LazyFrame,
+        spec: &HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        config: &HtsConfig,
+    ) -> Result<LazyFrame> {
+        let mut scan = scan;
+        let schema = scan.collect_schema()?;
+        spec.validate_schema(&schema)?;
+        for name in [time_col, value_col] {
+            if !schema.contains(name) {
+                return Err(HtsError::ColumnNotFound(name.to_string()));
+            }
+        }
+
+        let frames = Self::level_frames(scan, spec, time_col, value_col, None, false);
+        let lf = Self::union_levels(frames, config)?;
+        Ok(lf.with_streaming(true))
+    }
+
+    /// Builds the lazy group-by of every level over `bottom`, in
+    /// `level_combinations` order, reducing values with `value_agg` (output
+    /// as `value_col`), optionally with a `count` column of non-null
+    /// observations.
+    ///
+    /// When `value_agg` is `None` values are summed. Sums are linear, so the
+    /// bottom data is first reduced once to one row per (bottom series,
+    /// period) and every level is rolled up from that smaller frame; Polars
+    /// shares the common subplan between levels. Other reducers must see the
+    /// raw rows and are evaluated on `bottom` at every level.
+    fn level_frames(
+        bottom: LazyFrame,
+        spec: &HierarchySpec,
+        time_col: &str,
+        value_col: &str,
+        value_agg: Option<Expr>,
+        with_counts: bool,
+    ) -> Vec<LazyFrame> {
+        let all_cols = spec.all_columns();
+        // Get all combinations of columns that define the levels
+        let levels = spec.level_combinations();
+
+        let (source, value_agg, count_agg) = match value_agg {
+            Some(agg) => (bottom, agg, col(value_col).count()),
+            None => {
+                let mut group_cols: Vec<Expr> = all_cols.iter().map(|c| col(*c)).collect();
+                group_cols.push(col(time_col));
+
+                let mut aggs = vec![col(value_col).sum()];
+                if with_counts {
+                    aggs.push(col(value_col).count().alias("count"));
+                }
+                // Rolling up preserves first-appearance order, so the levels
+                // keep the row order of a direct group-by
+                let reduced = bottom.group_by_stable(group_cols).agg(aggs);
+                (
+                    reduced,
+                    col(value_col).sum(),
+                    col("count").sum().cast(IDX_DTYPE),
+                )
+            }
+        };
+
+        // We will collect lazy frames for each level and concat them
+        let mut frames = Vec::new();
+
+        for level_cols in levels {
+            // Group by the current level columns + time
+            let mut group_cols: Vec<Expr> = level_cols.iter().map(|c| col(c.as_str())).collect();
+            group_cols.push(col(time_col));
+
+            let mut aggs = vec![value_agg.clone().alias(value_col)];
+            if with_counts {
+                aggs.push(count_agg.clone().alias("count"));
+            }
+            let mut lf = source.clone().group_by_stable(group_cols).agg(aggs);
+
+            // Add missing columns as literals "<aggregated>"
+            for &col_name in &all_cols {
+                if !level_cols.contains(&col_name.to_string()) {
+                    lf = lf.with_column(lit(AGGREGATED_LABEL).alias(col_name));
+                }
+            }
+
+            // Select columns in consistent order
+            let mut select_cols: Vec<Expr> = all_cols.iter().map(|c| col(*c)).collect();
+            select_cols.push(col(time_col));
+            select_cols.push(col(value_col));
+            if with_counts {
+                select_cols.push(col("count"));
+            }
+
+            frames.push(lf.select(select_cols));
+        }
+
+        frames
+    }
+
+    /// Concatenates per-level frames, evaluating them concurrently when
+    /// [`HtsConfig::parallel`] is set and [`HtsConfig::n_threads`] allows
+    /// more than one thread.
+    fn union_levels(frames: Vec<LazyFrame>, config: &HtsConfig) -> Result<LazyFrame> {
+        let args = UnionArgs {
+            parallel: config.parallel && config.n_threads != Some(1),
+            maintain_order: true,
+            ..Default::default()
+        };
+        let concatenated = concat(frames, args)?;
+        Ok(concatenated)
+    }
+
+    /// Gets the values for a specific series across all time periods.
+    ///
+    /// # Arguments
+    ///
+    /// * `series_id` - The series identifier (e.g., "South Australia/Adelaide/Business")
+    ///
+    /// # Returns
+    ///
+    /// A vector of values for each time period, in [`Self::periods`] order,
+    /// or None if series not found. Periods without any observation under
+    /// the series are zero, as in [`Self::aggregate_all`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::NonAdditive`] for non-additive values without a
+    /// [weight column](HtsConfig::weight_col). In
+    /// [strict mode](HtsConfig::strict), returns [`HtsError::MissingData`]
+    /// whenever [`Self::aggregate_all`] would, rather than filling gaps with
+    /// zeros.
+    pub fn get_series(&self, series_id: &str) -> Result<Option<Vec<f64>>> {
+        let Some(node) = self.tree.get_node(series_id) else {
+            return Ok(None);
+        };
+        self.check_strict()?;
+
+        self.node_by_period(&node.labels).map(Some)
+    }
+
+    /// Returns the aggregated value of one series in one period.
+    ///
+    /// Only the bottom-level rows of that period under the series are
+    /// reduced, so a single cell can be looked up without materializing
+    /// [`Self::aggregate_all`]. The value is the one `aggregate_all` reports
+    /// for the same cell, or zero when the series has no observation in that
+    /// period.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::polars::prelude::*;
+    /// use hts_core::{HierarchicalTimeSeries, HierarchySpec, Period};
+    ///
+    /// let df = df! {
+    ///     "Quarter" => ["2024 Q1", "2024 Q2", "2024 Q2"],
+    ///     "State" => ["SP", "SP", "SP"],
+    ///     "Sector" => ["Industry", "Industry", "Services"],
+    ///     "Value" => [1.0, 2.0, 3.0],
+    /// }
+    /// .unwrap();
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into(), "Sector".into()]);
+    /// let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+    ///
+    /// let q2 = Period::Quarterly(2024, 2);
+    /// assert_eq!(hts.value_at("SP/Industry", q2).unwrap(), Some(2.0));
+    /// assert_eq!(hts.value_at("SP", q2).unwrap(), Some(5.0));
+    /// assert_eq!(hts.value_at("SP", Period::Quarterly(2024, 3)).unwrap(), None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// `None` if `period` is not one of [`Self::periods`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Hierarchy`] if `series_id` names no node, and
+    /// [`HtsError::NonAdditive`] for non-additive values without a
+    /// [weight column](HtsConfig::weight_col). In
+    /// [strict mode](HtsConfig::strict), returns [`HtsError::MissingData`]
+    /// whenever [`Self::aggregate_all`] would.
+    pub fn value_at(&self, series_id: &str, period: Period) -> Result<Option<f64>> {
+        let node = self
+            .tree
+            .get_node(series_id)
+            .ok_or_else(|| HtsError::Hierarchy(format!("Unknown series '{series_id}'")))?;
+        self.check_strict()?;
+        if !self.periods.contains(&period) {
+            return Ok(None);
+        }
+
+        // Parse each distinct time value once
+        let times = self
+            .bottom_data
+            .column(&self.time_col)?
+            .cast(&DataType::String)?;
+        let mut matches: HashMap<&str, bool> = HashMap::new();
+        let mut in_period = Vec::with_capacity(times.len());
+        for time in times.str()? {
+            let hit = match time.filter(|t| !t.trim().is_empty()) {
+                Some(t) => match matches.get(t) {
+                    Some(&hit) => hit,
+                    None => {
+                        let hit = Period::parse_as(t, self.config.period_format)? == period;
+                        matches.insert(t, hit);
+                        hit
+                    }
+                },
+                None => false,
+            };
+            in_period.push(hit);
+        }
+        let in_period = BooleanChunked::from_slice("in_period".into(), &in_period);
+
+        let mut lf = self.bottom_data.filter(&in_period)?.lazy();
+        for (column, label) in &node.labels {
+            lf = lf.filter(
+                col(column.as_str())
+                    .cast(DataType::String)
+                    .eq(lit(label.as_str())),
+            );
+        }
+        let agg = self
+            .value_agg()?
+            .unwrap_or_else(|| col(self.value_col.as_str()).cast(DataType::Float64).sum());
+        let value = lf.select([agg.alias(self.value_col.as_str())]).collect()?;
+
+        Ok(Some(
+            value
+                .column(&self.value_col)?
+                .f64()?
+                .get(0)
+                .unwrap_or_default(),
+        ))
+    }
+
+    /// Reconciles base forecasts given as a tidy DataFrame.
+    ///
+    /// Each row of `base` holds the base forecast for one series, identified
+    /// by its node id (e.g., `"South Australia/Adelaide/Business"`, or the
+    /// spec's `total_label` for the root). Rows are aligned to the summation
+    /// matrix by id, so their order does not matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Reconciliation method
+    /// * `base` - DataFrame of base forecasts
+    /// * `id_col` - Name of the series id column in `base`
+    /// * `value_col` - Name of the forecast column in `base`
+    ///
+    /// # Returns
+    ///
+    /// A DataFrame with `id_col` and `value_col` holding the reconciled
+    /// forecasts, one row per series in summation matrix row order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column is missing, if any series lacks a base
+    /// forecast (all such ids are listed), or if `base` contains unknown or
+    /// duplicated ids. Returns [`HtsError::NonAdditive`] if the values are
+    /// not additive (see [`HtsConfig::value_semantics`]).
+    pub fn reconcile_frame(
+        &self,
+        method: ReconMethod,
+        base: &DataFrame,
+        id_col: &str,
+        value_col: &str,
+    ) -> Result<DataFrame> {
+        self.check_additive()?;
+        let ids = base
+            .column(id_col)
+            .map_err(|_| HtsError::ColumnNotFound(id_col.to_string()))?
+            .cast(&DataType::String)?;
+        let values = base
+            .column(value_col)
+            .map_err(|_| HtsError::ColumnNotFound(value_col.to_string()))?
+            .cast(&DataType::Float64)?;
+        let ids = ids.str()?;
+        let values = values.f64()?;
+
+        let mut by_id: HashMap<&str, f64> = HashMap::with_capacity(base.height());
+        let mut unknown = Vec::new();
+        let mut duplicated = Vec::new();
+        for (id, value) in ids.into_iter().zip(values) {
+            let (Some(id), Some(value)) = (id, value) else {
+                continue;
+            };
+            if self.tree.get_node(id).is_none() {
+                unknown.push(id.to_string());
+            } else if by_id.insert(id, value).is_some() {
+                duplicated.push(id.to_string());
+            }
+        }
+
+        if !unknown.is_empty() {
+            return Err(HtsError::Hierarchy(format!(
+                "Base forecasts for unknown series: {}",
+                unknown.join(", ")
+            )));
+        }
+        if !duplicated.is_empty() {
+            return Err(HtsError::Reconciliation(format!(
+                "Duplicated base forecasts for series: {}",
+                duplicated.join(", ")
+            )));
+        }
+
+        let row_labels = self.s_matrix.row_labels();
+        let missing: Vec<&str> = row_labels
+            .iter()
+            .map(String::as_str)
+            .filter(|id| !by_id.contains_key(id))
+            .collect();
+        if !missing.is_empty() {
+            return Err(HtsError::Reconciliation(format!(
+                "No base forecast for series: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let y_hat: Vec<f64> = row_labels.iter().map(|id| by_id[id.as_str()]).collect();
+        let y_tilde = self.install(|| self.s_matrix.reconcile(&y_hat, method))?;
+
+        let df = DataFrame::new(vec![
+            Column::new(id_col.into(), row_labels),
+            Column::new(value_col.into(), y_tilde),
+        ])?;
+        Ok(df)
+    }
+
+    /// Reconciles base forecasts laid out like the output of
+    /// [`Self::aggregate_all`].
+    ///
+    /// `base` must have the key columns, the time column, and the value
+    /// column of this dataset, with [`AGGREGATED_LABEL`] in the key columns a
+    /// series aggregates over. Each period is reconciled independently. The
+    /// result is `base` with its value column replaced by the reconciled
+    /// forecasts, so it has the same schema and row order and can be compared
+    /// with `base` cell by cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column is missing, if a row names an unknown
+    /// series, or if a period does not have exactly one non-null base
+    /// forecast for every series. Returns [`HtsError::NonAdditive`] if the
+    /// values are not additive (see [`HtsConfig::value_semantics`]).
+    pub fn reconciled_frame(&self, base: &DataFrame, method: ReconMethod) -> Result<DataFrame> {
+        self.check_additive()?;
+        let result = self.reconcile_periods(base, &self.value_col, method)?;
+
+        let reconciled: Vec<f64> = result
+            .cells
+            .iter()
+            .map(|&(t, i)| result.values[t][i])
+            .collect();
+        let mut out = base.clone();
+        out.with_column(Column::new(self.value_col.as_str().into(), reconciled))?;
+        Ok(out)
+    }
+
+    /// Reconciles several value columns of a frame laid out like the output
+    /// of [`Self::aggregate_all`], each with its own method.
+    ///
+    /// `base` is read as in [`Self::reconciled_frame`], with `methods`
+    /// naming the value columns to reconcile. The result is tidy: one row
+    /// per (period, series), with the series `id`, its `level` (0 = total),
+    /// the time column and one column per reconciled quantity, in name
+    /// order. Periods keep their order of first appearance in `base`, and
+    /// series are in summation matrix row order within each period.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::{HierarchicalTimeSeries, HierarchySpec, ReconMethod};
+    /// use hts_core::polars::prelude::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let df = df! {
+    ///     "Quarter" => ["1998 Q1", "1998 Q1"],
+    ///     "State" => ["A", "B"],
+    ///     "Trips" => [1.0, 2.0],
+    /// }.unwrap();
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+    /// let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Trips").unwrap();
+    ///
+    /// let base = hts
+    ///     .aggregate_all()
+    ///     .unwrap()
+    ///     .lazy()
+    ///     .with_column((col("Trips") * lit(10.0)).alias("Nights"))
+    ///     .collect()
+    ///     .unwrap();
+    /// let methods = HashMap::from([
+    ///     ("Trips".to_string(), ReconMethod::BottomUp),
+    ///     ("Nights".to_string(), ReconMethod::Ols),
+    /// ]);
+    /// let out = hts.reconcile_all_to_frame(&base, &methods).unwrap();
+    /// assert_eq!(out.get_column_names(), ["id", "level", "Quarter", "Nights", "Trips"]);
+    /// assert_eq!(out.height(), 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if `methods` is empty, and the
+    /// errors of [`Self::reconciled_frame`] for each column; the non-additive
+    /// check applies to this dataset's own value column only.
+    pub fn reconcile_all_to_frame(
+        &self,
+        base: &DataFrame,
+        methods: &HashMap<String, ReconMethod>,
+    ) -> Result<DataFrame> {
+        if methods.is_empty() {
+            return Err(HtsError::Reconciliation(
+                "No value columns to reconcile".to_string(),
+            ));
+        }
+        if methods.contains_key(&self.value_col) {
+            self.check_additive()?;
+        }
+
+        let mut names: Vec<&String> = methods.keys().collect();
+        names.sort();
+
+        let n = self.n_series();
+        let mut periods = Vec::new();
+        let mut columns = Vec::with_capacity(names.len());
+        for name in names {
+            let result = self.reconcile_periods(base, name, methods[name])?;
+            periods = result.periods;
+            let values: Vec<f64> = result.values.into_iter().flatten().collect();
+            columns.push(Column::new(name.as_str().into(), values));
+        }
+
+        let ids: Vec<&str> = self
+            .s_matrix
+            .row_labels()
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let levels: Vec<u32> = self.tree.nodes().iter().map(|n| n.level as u32).collect();
+        let mut out = vec![
+            Column::new("id".into(), ids.repeat(periods.len())),
+            Column::new("level".into(), levels.repeat(periods.len())),
+            Column::new(
+                self.time_col.as_str().into(),
+                periods
+                    .iter()
+                    .flat_map(|p| std::iter::repeat_n(p.as_str(), n))
+                    .collect::<Vec<_>>(),
+            ),
+        ];
+        out.extend(columns);
+        Ok(DataFrame::new(out)?)
+    }
+
+    /// Reconciles `value_col` of a frame in the [`Self::aggregate_all`]
+    /// layout period by period.
+    fn reconcile_periods(
+        &self,
+        base: &DataFrame,
+        value_col: &str,
+        method: ReconMethod,
+    ) -> Result<PeriodReconciliation> {
+        let string_column = |name: &str| -> Result<StringChunked> {
+            let column = base
+                .column(name)
+                .map_err(|_| HtsError::ColumnNotFound(name.to_string()))?
+                .cast(&DataType::String)?;
+            Ok(column.str()?.clone())
+        };
+
+        let all_cols = self.spec.all_columns();
+        let keys = all_cols
+            .iter()
+            .map(|c| string_column(c))
+            .collect::<Result<Vec<_>>>()?;
+        let times = string_column(&self.time_col)?;
+        let values = base
+            .column(value_col)
+            .map_err(|_| HtsError::ColumnNotFound(value_col.to_string()))?
+            .cast(&DataType::Float64)?;
+        let values = values.f64()?;
+
+        let row_index: HashMap<&str, usize> = self
+            .s_matrix
+            .row_labels()
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i))
+            .collect();
+        let n = self.n_series();
+
+        // Base forecasts per period, in S row order, and the (period, row)
+        // cell each frame row maps to
+        let mut period_index: HashMap<String, usize> = HashMap::new();
+        let mut y_hat: Vec<Vec<Option<f64>>> = Vec::new();
+        let mut cells = Vec::with_capacity(base.height());
+
+        for r in 0..base.height() {
+            let mut labels = HashMap::new();
+            for (name, key) in all_cols.iter().zip(&keys) {
+                if let Some(label) = key.get(r)
+                    && label != AGGREGATED_LABEL
+                {
+                    labels.insert(name.to_string(), label.to_string());
+                }
+            }
+            let id = self.spec.series_id(&labels);
+            let &i = row_index.get(id.as_str()).ok_or_else(|| {
+                HtsError::Hierarchy(format!("Row {r} refers to unknown series '{id}'"))
+            })?;
+
+            let period = times.get(r).unwrap_or_default().trim().to_string();
+            let next = y_hat.len();
+            let t = *period_index.entry(period.clone()).or_insert(next);
+            if t == next {
+                y_hat.push(vec![None; n]);
+            }
+
+            if y_hat[t][i].is_some() {
+                return Err(HtsError::Reconciliation(format!(
+                    "Duplicated base forecast for series '{id}' in period '{period}'"
+                )));
+            }
+            y_hat[t][i] = values.get(r);
+            cells.push((t, i));
+        }
+
+        let g = self.install(|| ReconciliationMatrix::new(&self.s_matrix, method))?;
+        let mut periods: Vec<String> = vec![String::new(); period_index.len()];
+        for (period, t) in period_index {
+            periods[t] = period;
+        }
+
+        let mut y_tilde = Vec::with_capacity(y_hat.len());
+        for (t, period) in periods.iter().enumerate() {
+            let missing: Vec<&str> = y_hat[t]
+                .iter()
+                .zip(self.s_matrix.row_labels())
+                .filter(|(v, _)| v.is_none())
+                .map(|(_, id)| id.as_str())
+                .collect();
+            if !missing.is_empty() {
+                return Err(HtsError::Reconciliation(format!(
+                    "No base forecast in period '{period}' for series: {}",
+                    missing.join(", ")
+                )));
+            }
+
+            let base_values: Vec<f64> = y_hat[t].iter().map(|v| v.unwrap_or_default()).collect();
+            y_tilde.push(self.install(|| g.reconcile(&base_values))?);
+        }
+
+        Ok(PeriodReconciliation {
+            periods,
+            values: y_tilde,
+            cells,
+        })
+    }
+
+    /// Returns the sorted distinct values of a hierarchy or group column.
+    ///
+    /// Values are sorted and stringified exactly as when building node ids,
+    /// so they can be matched against [`Node::labels`](crate::Node::labels).
+    /// Nulls are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `column` is not part of the spec.
+    pub fn distinct_values(&self, column: &str) -> Result<Vec<String>> {
+        if !self.spec.all_columns().contains(&column) {
+            return Err(HtsError::Hierarchy(format!(
+                "Column '{column}' is not part of the hierarchy specification"
+            )));
+        }
+
+        let unique = self
+            .bottom_data
+            .clone()
+            .lazy()
+            .select([col(column)])
+            .drop_nulls(None)
+            .unique(None, UniqueKeepStrategy::First)
+            .sort([column], SortMultipleOptions::default())
+            .collect()?;
+        let unique = crate::hierarchy::sort_labels(unique, &[column], self.config.collation)?;
+
+        let values = unique.column(column)?;
+        (0..values.len())
+            .map(|i| Ok(values.get(i)?.to_string().trim_matches('"').to_string()))
+            .collect()
+    }
+
+    /// Returns the ids of bottom-level series whose values are all zero or
+    /// null over the observed periods.
+    ///
+    /// Such series usually point to an upstream data problem, and they leave
+    /// nothing to split when computing top-down proportions. Ids are returned
+    /// in bottom-level order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Polars query fails.
+    pub fn zero_series(&self) -> Result<Vec<String>> {
+        let all_cols = self.spec.all_columns();
+        let zero = self
+            .bottom_data
+            .clone()
+            .lazy()
+            .group_by_stable(all_cols.iter().map(|c| col(*c)).collect::<Vec<_>>())
+            .agg([col(self.value_col.as_str())
+                .fill_null(lit(0))
+                .neq(lit(0))
+                .cast(DataType::UInt32)
+                .sum()
+                .alias("n_nonzero")])
+            .filter(col("n_nonzero").eq(lit(0)))
+            .collect()?;
+
+        let ids = (0..zero.height())
+            .map(|row| {
+                let mut labels = HashMap::new();
+                for &c in &all_cols {
+                    let value = zero.column(c)?.get(row)?.to_string();
+                    labels.insert(c.to_string(), value.trim_matches('"').to_string());
+                }
+                Ok(self.spec.series_id(&labels))
+            })
+            .collect::<Result<HashSet<_>>>()?;
+
+        // Follow the tree's bottom-level order, which honors the collation
+        Ok(self
+            .s_matrix
+            .col_labels()
+            .iter()
+            .filter(|id| ids.contains(*id))
+            .cloned()
+            .collect())
+    }
+
+    /// Sums the bottom-level data into an m × T matrix, with rows in S column
+    /// order and columns in [`Self::periods`] order, along with the matching
+    /// matrix of non-null observation counts.
+    ///
+    /// Missing observations are zero, and rows without a time value are
+    /// skipped.
+    fn bottom_matrix(&self) -> Result<(Mat<f64>, Mat<f64>)> {
+        let all_cols = self.spec.all_columns();
+        let mut group_cols: Vec<Expr> = all_cols.iter().map(|c| col(*c)).collect();
+        group_cols.push(col(self.time_col.as_str()));
+
+        let grouped = self
+            .bottom_data
+            .clone()
+            .lazy()
+            .group_by(group_cols)
+            .agg([
+                col(self.value_col.as_str()).cast(DataType::Float64).sum(),
+                col(self.value_col.as_str()).count().alias("count"),
+            ])
+            .collect()?;
+
+        let keys = all_cols
+            .iter()
+            .map(|c| Ok(grouped.column(c)?.cast(&DataType::String)?.str()?.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let times = grouped.column(&self.time_col)?.cast(&DataType::String)?;
+        let times = times.str()?;
+        let values = grouped.column(&self.value_col)?.f64()?.clone();
+        let counts = grouped.column("count")?.cast(&DataType::Float64)?;
+        let counts = counts.f64()?;
+
+        let col_index: HashMap<&str, usize> = self
+            .s_matrix
+            .col_labels()
+            .iter()
+            .enumerate()
+            .map(|(j, id)| (id.as_str(), j))
+            .collect();
+        let period_index: HashMap<Period, usize> = self
+            .periods
+            .iter()
+            .enumerate()
+            .map(|(t, &p)| (p, t))
+            .collect();
+
+        let mut b = Mat::zeros(self.n_bottom(), self.n_periods());
+        let mut n = Mat::zeros(self.n_bottom(), self.n_periods());
+        for r in 0..grouped.height() {
+            let Some(time) = times.get(r).filter(|t| !t.trim().is_empty()) else {
+                continue;
+            };
+            let t = period_index[&Period::parse_as(time, self.config.period_format)?];
+
+            let mut labels = HashMap::new();
+            for (name, key) in all_cols.iter().zip(&keys) {
+                if let Some(label) = key.get(r) {
+                    labels.insert(name.to_string(), label.to_string());
+                }
+            }
+            let id = self.spec.series_id(&labels);
+            let &j = col_index.get(id.as_str()).ok_or_else(|| {
+                HtsError::Hierarchy(format!("Bottom-level series '{id}' has no column in S"))
+            })?;
+
+            b[(j, t)] += values.get(r).unwrap_or_default();
+            n[(j, t)] += counts.get(r).unwrap_or_default();
+        }
+
+        Ok((b, n))
+    }
+
+    /// Lists the (bottom series, period) cells without any non-null
+    /// observation in the bottom-level data.
+    ///
+    /// Aggregation treats such cells as zero; fill them explicitly before
+    /// aggregating when that is not acceptable, or enable
+    /// [`HtsConfig::strict`] to have aggregation refuse them.
+    ///
+    /// # Returns
+    ///
+    /// A DataFrame with an `id` column holding the bottom-level series id and
+    /// the time column holding the period, ordered by series in S column
+    /// order, then by period.
+    pub fn missing_cells(&self) -> Result<DataFrame> {
+        let (_, counts) = self.bottom_matrix()?;
+
+        let mut ids = Vec::new();
+        let mut periods = Vec::new();
+        for (j, id) in self.s_matrix.col_labels().iter().enumerate() {
+            for (t, period) in self.periods.iter().enumerate() {
+                if counts[(j, t)] == 0.0 {
+                    ids.push(id.as_str());
+                    periods.push(period.to_string());
+                }
+            }
+        }
+
+        let df = DataFrame::new(vec![
+            Column::new("id".into(), ids),
+            Column::new(self.time_col.as_str().into(), periods),
+        ])?;
+        Ok(df)
+    }
+
+    /// Reports, for every node, in how many periods all of its bottom-level
+    /// series have data.
+    ///
+    /// An aggregate looks complete even when a constituent series has gaps,
+    /// because [`Self::aggregate_all`] zero-fills them. Here a node counts a
+    /// period as complete only if every bottom series it aggregates has a
+    /// non-null observation in it (see [`Self::missing_cells`]).
+    ///
+    /// # Returns
+    ///
+    /// A DataFrame with one row per node, in S row order, and the columns
+    /// `id`, `level`, `complete_periods`, `expected_periods` (the number of
+    /// periods in the dataset), and `completeness`, their ratio.
+    pub fn node_completeness(&self) -> Result<DataFrame> {
+        let (_, counts) = self.bottom_matrix()?;
+        let n_periods = self.periods.len();
+
+        // Periods observed for every bottom series in each node
+        let mut complete = Vec::with_capacity(self.tree.len());
+        for node in self.tree.nodes() {
+            let n = (0..n_periods)
+                .filter(|&t| node.aggregates_from.iter().all(|&j| counts[(j, t)] > 0.0))
+                .count();
+            complete.push(n as u32);
+        }
+
+        let completeness: Vec<f64> = complete
+            .iter()
+            .map(|&n| {
+                if n_periods == 0 {
+                    0.0
+                } else {
+                    n as f64 / n_periods as f64
+                }
+            })
+            .collect();
+        let df = DataFrame::new(vec![
+            Column::new("id".into(), self.tree.all_ids()),
+            Column::new(
+                "level".into(),
+                self.tree
+                    .nodes()
+                    .iter()
+                    .map(|n| n.level as u32)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new("complete_periods".into(), complete),
+            Column::new(
+                "expected_periods".into(),
+                vec![n_periods as u32; self.tree.len()],
+            ),
+            Column::new("completeness".into(), completeness),
+        ])?;
+        Ok(df)
+    }
+
+    /// Returns the reduction of the value column for aggregation: `None` for
+    /// plain sums, or the weighted mean for non-additive values.
+    fn value_agg(&self) -> Result<Option<Expr>> {
+        if self.config.value_semantics.is_additive() {
+            return Ok(None);
+        }
+        let Some(weight_col) = &self.config.weight_col else {
+            return Err(HtsError::NonAdditive(format!(
+                "values of '{}' are {:?} and cannot be summed; set HtsConfig::weight_col \
+                 to aggregate them as weighted means",
+                self.value_col, self.config.value_semantics
+            )));
+        };
+
+        Ok(Some(Self::weighted_mean(&self.value_col, weight_col)))
+    }
+
+    /// Mean of `value_col` weighted by `weight_col`, ignoring the weights of
+    /// null values.
+    fn weighted_mean(value_col: &str, weight_col: &str) -> Expr {
+        let value = col(value_col).cast(DataType::Float64);
+        let weight = col(weight_col).cast(DataType::Float64);
+        let weighted = (value.clone() * weight.clone()).sum();
+        let total_weight = weight.filter(value.is_not_null()).sum();
+        weighted / total_weight
+    }
+
+    /// Fails if the values are not additive, so the summation matrix does not
+    /// relate the levels.
+    fn check_additive(&self) -> Result<()> {
+        if self.config.value_semantics.is_additive() {
+            return Ok(());
+        }
+        Err(HtsError::NonAdditive(format!(
+            "values of '{}' are {:?}; their aggregates are weighted means, which the \
+             summation matrix cannot reconcile",
+            self.value_col, self.config.value_semantics
+        )))
+    }
+
+    /// Fails in strict mode if any bottom-level cell would be zero-filled.
+    fn check_strict(&self) -> Result<()> {
+        if !self.config.strict {
+            return Ok(());
+        }
+
+        let missing = self.missing_cells()?;
+        if missing.height() > 0 {
+            let ids = missing.column("id")?.str()?.clone();
+            let periods = missing.column(&self.time_col)?.str()?.clone();
+            let first: Vec<String> = ids
+                .into_iter()
+                .zip(&periods)
+                .take(5)
+                .map(|(id, period)| format!("{} @ {}", id.unwrap_or(""), period.unwrap_or("")))
+                .collect();
+            return Err(HtsError::MissingData(format!(
+                "{} bottom-level cell(s) have no observation (first: {}); fill them explicitly \
+                 or disable strict mode",
+                missing.height(),
+                first.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Computes the top-down disaggregation proportions of each bottom-level
+    /// series, so they can be audited before being applied.
+    ///
+    /// Top-down reconciliation splits the total forecast among the bottom
+    /// series in these proportions; see [`TopDownMethod`] for how they are
+    /// derived from the historical data.
+    ///
+    /// # Returns
+    ///
+    /// A DataFrame with an `id` column holding the bottom-level series ids, in
+    /// S column order, and a `proportion` column.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if the proportions do not sum to
+    /// one within each parent: when the total is zero in every period, or
+    /// when an aggregated series gets a zero share (e.g., a region that is
+    /// zero throughout), which leaves its children's split undefined.
+    pub fn top_down_proportions(&self, method: TopDownMethod) -> Result<DataFrame> {
+        let (b, _) = self.bottom_matrix()?;
+        let (m, t) = (b.nrows(), b.ncols());
+        let totals: Vec<f64> = (0..t).map(|k| (0..m).map(|j| b[(j, k)]).sum()).collect();
+
+        let proportions: Vec<f64> = match method {
+            TopDownMethod::AverageHistoricalProportions => {
+                let observed: Vec<usize> = (0..t).filter(|&k| totals[k] != 0.0).collect();
+                (0..m)
+                    .map(|j| {
+                        observed.iter().map(|&k| b[(j, k)] / totals[k]).sum::<f64>()
+                            / observed.len() as f64
+                    })
+                    .collect()
+            }
+            TopDownMethod::ProportionsOfHistoricalAverages => {
+                // The 1/T factors of both means cancel out
+                let grand_total: f64 = totals.iter().sum();
+                (0..m)
+                    .map(|j| (0..t).map(|k| b[(j, k)]).sum::<f64>() / grand_total)
+                    .collect()
+            }
+        };
+
+        let tol = Tolerance::default();
+        let sum: f64 = proportions.iter().sum();
+        if !tol.approx_eq(sum, 1.0) {
+            return Err(HtsError::Reconciliation(format!(
+                "Top-down proportions sum to {sum} instead of 1; the total may be zero in \
+                 every period"
+            )));
+        }
+
+        // Within each parent, the children's shares of the parent's share
+        // must sum to one
+        let shares = self.s_matrix.aggregate(&proportions);
+        let mut children_sums = vec![0.0; shares.len()];
+        let mut has_children = vec![false; shares.len()];
+        for (child, parent) in self.tree.parent_map().into_iter().enumerate() {
+            if let Some(parent) = parent {
+                children_sums[parent] += shares[child];
+                has_children[parent] = true;
+            }
+        }
+        let unbalanced: Vec<String> = (0..shares.len())
+            .filter(|&i| {
+                has_children[i]
+                    && !(shares[i] > 0.0 && tol.approx_eq(children_sums[i] / shares[i], 1.0))
+            })
+            .map(|i| format!("'{}' ({})", self.s_matrix.row_labels()[i], shares[i]))
+            .collect();
+        if !unbalanced.is_empty() {
+            return Err(HtsError::Reconciliation(format!(
+                "Top-down proportions do not sum to 1 within {} parent(s) (first: {}); a \
+                 series with a zero share leaves its children's split undefined",
+                unbalanced.len(),
+                unbalanced[..unbalanced.len().min(5)].join(", ")
+            )));
+        }
+
+        let df = DataFrame::new(vec![
+            Column::new("id".into(), self.s_matrix.col_labels()),
+            Column::new("proportion".into(), proportions),
+        ])?;
+        Ok(df)
+    }
+
+    /// Computes the average value of every series in each season.
+    ///
+    /// Aggregates all levels like [`Self::aggregate_all`], then averages each
+    /// series over the periods sharing a [`Period::season_index`], i.e. per
+    /// quarter, month or ISO week. Useful as a descriptive statistic before
+    /// fitting any model.
+    ///
+    /// # Returns
+    ///
+    /// A DataFrame with the grouping columns, a `season` column and the
+    /// value column holding the mean, in the row order of `aggregate_all`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::InvalidPeriod`] if the periods are annual, daily
+    /// or ordinal, which have no season.
+    pub fn seasonal_means(&self) -> Result<DataFrame> {
+        if let Some(period) = self.periods.iter().find(|p| p.season_index().is_none()) {
+            return Err(HtsError::InvalidPeriod(format!(
+                "Period '{period}' has no season; seasonal means need quarterly, monthly or \
+                 weekly data"
+            )));
+        }
+
+        let mut df = self.aggregate_all()?;
+        let times = df.column(&self.time_col)?.cast(&DataType::String)?;
+        let season: Vec<Option<u32>> = times
+            .str()?
+            .into_iter()
+            .map(|t| {
+                Period::parse_as(t?, self.config.period_format)
+                    .ok()?
+                    .season_index()
+            })
+            .collect();
+        df.with_column(Column::new("season".into(), season))?;
+
+        let mut group_cols: Vec<Expr> = self.spec.all_columns().iter().map(|c| col(*c)).collect();
+        group_cols.push(col("season"));
+
+        let means = df
+            .lazy()
+            .group_by_stable(group_cols)
+            .agg([col(self.value_col.as_str()).cast(DataType::Float64).mean()])
+            .collect()?;
+        Ok(means)
+    }
+
+    /// Compares the accuracy of base and reconciled forecasts against
+    /// held-out actuals, level by level.
+    ///
+    /// `base`, `reconciled` and `actual` are frames in the
+    /// [`Self::aggregate_all`] layout (e.g., `reconciled` from
+    /// [`Self::reconciled_frame`]). Every (series, period) of `actual` is
+    /// scored, so both forecast frames must cover it. Rows without a period
+    /// are skipped, in the three frames as in the in-sample history. Series
+    /// are grouped into levels by [`SummationMatrix::row_level_labels`].
+    ///
+    /// RMSE pools the squared errors of a level. MASE scales each series'
+    /// mean absolute error by the in-sample mean absolute seasonal naive
+    /// error of this dataset (lag 4 for quarterly, 12 for monthly and 52 for
+    /// weekly data, 1 otherwise), then averages over the series of the level
+    /// whose scale is positive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if a forecast is missing for an
+    /// actual value, or an error if a frame lacks a column or names an
+    /// unknown series.
+    pub fn reconciliation_report(
+        &self,
+        base: &DataFrame,
+        reconciled: &DataFrame,
+        actual: &DataFrame,
+    ) -> Result<ReconReport> {
+        let row_index: HashMap<&str, usize> = self
+            .s_matrix
+            .row_labels()
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i))
+            .collect();
+        let row_of = |id: &str| {
+            row_index
+                .get(id)
+                .copied()
+                .ok_or_else(|| HtsError::Hierarchy(format!("Unknown series '{id}'")))
+        };
+
+        // In-sample history, one row per series, to scale MASE
+        let (n, n_periods) = (self.n_series(), self.n_periods());
+        let period_index: HashMap<Period, usize> = self
+            .periods
+            .iter()
+            .enumerate()
+            .map(|(t, &p)| (p, t))
+            .collect();
+        let mut history = Mat::<f64>::zeros(n, n_periods);
+        let (ids, periods, values) = self.frame_cells(&self.aggregate_all()?)?;
+        for ((id, period), value) in ids.iter().zip(&periods).zip(&values) {
+            history[(row_of(id)?, period_index[period])] = value.unwrap_or_default();
+        }
+        let lag = match self.periods.first() {
+            Some(Period::Quarterly(..)) => 4,
+            Some(Period::Monthly(..)) => 12,
+            Some(Period::Weekly(..)) => 52,
+            _ => 1,
+        };
+        let lag = if lag < n_periods { lag } else { 1 };
+        let scales: Vec<f64> = (0..n)
+            .map(|i| {
+                let diffs = lag..n_periods;
+                let count = diffs.len() as f64;
+                diffs
+                    .map(|t| (history[(i, t)] - history[(i, t - lag)]).abs())
+                    .sum::<f64>()
+                    / count
+            })
+            .collect();
+
+        let forecasts = |df: &DataFrame| -> Result<HashMap<(usize, Period), f64>> {
+            let (ids, periods, values) = self.frame_cells(df)?;
+            let mut map = HashMap::with_capacity(ids.len());
+            for ((id, period), value) in ids.iter().zip(periods).zip(&values) {
+                if let Some(value) = value {
+                    map.insert((row_of(id)?, period), value);
+                }
+            }
+            Ok(map)
+        };
+        let base = forecasts(base)?;
+        let reconciled = forecasts(reconciled)?;
+
+        // Per series: squared and absolute errors of base and reconciled
+        let mut errors = vec![ErrorSums::default(); n];
+        let (ids, periods, values) = self.frame_cells(actual)?;
+        for ((id, period), value) in ids.iter().zip(periods).zip(&values) {
+            let Some(value) = value else {
+                continue;
+            };
+            let row = row_of(id)?;
+            let lookup = |map: &HashMap<(usize, Period), f64>, kind: &str| {
+                map.get(&(row, period)).copied().ok_or_else(|| {
+                    HtsError::Reconciliation(format!("No {kind} forecast for '{id}' in {period}"))
+                })
+            };
+            let e_base = value - lookup(&base, "base")?;
+            let e_rec = value - lookup(&reconciled, "reconciled")?;
+            let acc = &mut errors[row];
+            acc.cells += 1;
+            acc.sq_base += e_base * e_base;
+            acc.sq_reconciled += e_rec * e_rec;
+            acc.abs_base += e_base.abs();
+            acc.abs_reconciled += e_rec.abs();
+        }
+
+        // Pool series into levels, in row order of first appearance
+        let mut levels: Vec<LevelAccuracy> = Vec::new();
+        let mut level_sums: Vec<ErrorSums> = Vec::new();
+        let mut level_index: HashMap<&str, usize> = HashMap::new();
+        for (row, level) in self.s_matrix.row_level_labels().iter().enumerate() {
+            if errors[row].cells == 0 {
+                continue;
+            }
+            let k = *level_index.entry(level.as_str()).or_insert_with(|| {
+                levels.push(LevelAccuracy {
+                    level: level.clone(),
+                    n_series: 0,
+                    rmse_base: 0.0,
+                    rmse_reconciled: 0.0,
+                    mase_base: f64::NAN,
+                    mase_reconciled: f64::NAN,
+                });
+                level_sums.push(ErrorSums::default());
+                levels.len() - 1
+            });
+
+            // Squared errors pool over cells; absolute errors are scaled per
+            // series, then pool over the series with a positive scale
+            let acc = errors[row];
+            let sums = &mut level_sums[k];
+            levels[k].n_series += 1;
+            sums.cells += acc.cells;
+            sums.sq_base += acc.sq_base;
+            sums.sq_reconciled += acc.sq_reconciled;
+            if scales[row] > 0.0 && scales[row].is_finite() {
+                let c = acc.cells as f64;
+                sums.scaled_series += 1;
+                sums.abs_base += acc.abs_base / c / scales[row];
+                sums.abs_reconciled += acc.abs_reconciled / c / scales[row];
+            }
+        }
+        for (level, sums) in levels.iter_mut().zip(level_sums) {
+            level.rmse_base = (sums.sq_base / sums.cells as f64).sqrt();
+            level.rmse_reconciled = (sums.sq_reconciled / sums.cells as f64).sqrt();
+            if sums.scaled_series > 0 {
+                level.mase_base = sums.abs_base / sums.scaled_series as f64;
+                level.mase_reconciled = sums.abs_reconciled / sums.scaled_series as f64;
+            }
+        }
+
+        Ok(ReconReport { levels })
+    }
+
+    /// Returns a summary of the hierarchical structure.
+    pub fn summary(&self) -> HtsSummary {
+        HtsSummary {
+            n_series: self.n_series(),
+            n_bottom: self.n_bottom(),
+            n_periods: self.n_periods(),
+            hierarchy_cols: self.spec.hierarchy.clone(),
+            group_cols: self.spec.groups.clone(),
+            s_matrix_shape: self.s_matrix.shape(),
+        }
+    }
+}
+
+/// Running error sums of one series or level, for
+/// [`HierarchicalTimeSeries::reconciliation_report`].
+#[derive(Debug, Clone, Copy, Default)]
+struct ErrorSums {
+    /// Number of scored (series, period) cells.
+    cells: usize,
+    /// Sum of squared base errors.
+    sq_base: f64,
+    /// Sum of squared reconciled errors.
+    sq_reconciled: f64,
+    /// Sum of absolute base errors; for a level, of scaled mean absolute
+    /// errors.
+    abs_base: f64,
+    /// Sum of absolute reconciled errors; for a level, of scaled mean
+    /// absolute errors.
+    abs_reconciled: f64,
+    /// Number of series of a level with a positive MASE scale.
+    scaled_series: usize,
+}
+
+/// Base forecasts reconciled period by period, from
+/// [`HierarchicalTimeSeries::reconcile_periods`].
+#[derive(Debug)]
+struct PeriodReconciliation {
+    /// Periods, as written in the frame, in order of first appearance.
+    periods: Vec<String>,
+    /// Reconciled values of each period, in S row order.
+    values: Vec<Vec<f64>>,
+    /// (period, row) cell of each frame row.
+    cells: Vec<(usize, usize)>,
+}
+
+/// Outcome of comparing external aggregates against recomputed ones, from
+/// [`HierarchicalTimeSeries::reconcile_external`].
+#[derive(Debug, Clone, Default)]
+pub struct DiffReport {
+    /// Number of external rows matched to a recomputed aggregate.
+    pub n_compared: usize,
+    /// Matched rows whose values disagree beyond the tolerance.
+    pub mismatches: Vec<ValueDiff>,
+    /// External rows, as (series id, period), with no recomputed
+    /// counterpart.
+    pub unmatched: Vec<(String, String)>,
+}
+
+impl DiffReport {
+    /// Returns true if every external row matched and agreed.
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty() && self.unmatched.is_empty()
+    }
+}
+
+/// A single disagreement in a [`DiffReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueDiff {
+    /// Series id.
+    pub id: String,
+    /// Period, formatted like [`Period`]'s `Display`.
+    pub period: String,
+    /// Value recomputed from the bottom-level data.
+    pub expected: f64,
+    /// External value, or `None` if it was null.
+    pub actual: Option<f64>,
+}
+
+/// Timings and sizes of the build steps, from
+/// [`HierarchicalTimeSeries::new_with_diagnostics`].
+#[derive(Debug, Clone, Default)]
+pub struct BuildDiagnostics {
+    /// Time spent parsing the time column into periods.
+    pub parse_periods: Duration,
+    /// Time spent building the hierarchy tree.
+    pub build_tree: Duration,
+    /// Time spent building the summation matrix.
+    pub build_s_matrix: Duration,
+    /// Number of bottom-level rows after loading options were applied.
+    pub n_rows: usize,
+    /// Number of nodes (series) in the hierarchy tree.
+    pub n_nodes: usize,
+    /// Number of parent-child links, as in
+    /// [`HierarchyTree::parent_map`].
+    pub n_edges: usize,
+}
+
+impl std::fmt::Display for BuildDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Build Diagnostics")?;
+        writeln!(f, "=================")?;
+        writeln!(f, "Period parsing: {:?}", self.parse_periods)?;
+        writeln!(f, "Tree build:     {:?}", self.build_tree)?;
+        writeln!(f, "S matrix:       {:?}", self.build_s_matrix)?;
+        writeln!(f, "Rows:           {}", self.n_rows)?;
+        writeln!(f, "Nodes:          {}", self.n_nodes)?;
+        writeln!(f, "Edges:          {}", self.n_edges)?;
+        Ok(())
+    }
+}
+
+/// Summary of a hierarchical time series structure.
+#[derive(Debug, Clone)]
+pub struct HtsSummary {
+    /// Total number of series.
+    pub n_series: usize,
+    /// Number of bottom-level series.
+    pub n_bottom: usize,
+    /// Number of time periods.
+    pub n_periods: usize,
+    /// Hierarchical columns.
+    pub hierarchy_cols: Vec<String>,
+    /// Grouped columns.
+    pub group_cols: Vec<String>,
+    /// Shape of S matrix (n, m).
+    pub s_matrix_shape: (usize, usize),
+}
+
+impl std::fmt::Display for HtsSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Hierarchical Time Series Summary")?;
+        writeln!(f, "================================")?;
+        writeln!(f, "Total series:  {}", self.n_series)?;
+        writeln!(f, "Bottom series: {}", self.n_bottom)?;
+        writeln!(f, "Time periods:  {}", self.n_periods)?;
+        writeln!(f, "Hierarchy:     {:?}", self.hierarchy_cols)?;
+        writeln!(f, "Groups:        {:?}", self.group_cols)?;
+        writeln!(
+            f,
+            "S matrix:      {} × {}",
+            self.s_matrix_shape.0, self.s_matrix_shape.1
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AggregationFn, Collation, PeriodFormat, ValueKind};
+    use crate::hierarchy::DEFAULT_TOTAL_LABEL;
+
+    #[test]
+    fn test_hts_from_dataframe() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q2", "1998 Q1", "1998 Q2"],
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AA", "BA", "BA"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        let summary = hts.summary();
+        println!("{}", summary);
+
+        assert_eq!(hts.n_periods(), 2);
+        assert!(hts.n_series() >= 2);
+    }
+
+    #[test]
+    fn test_hts_summary() {
+        let df = df! {
+            "Quarter" => vec!["2024 M01"; 8],
+            "State" => [vec!["A"; 4], vec!["B"; 4]].concat(),
+            "Region" => [vec!["AA"; 2], vec!["AB"; 2], vec!["BA"; 2], vec!["BB"; 2]].concat(),
+            "Purpose" => ["X", "Y", "X", "Y", "X", "Y", "X", "Y"],
+            "Value" => [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::new(
+            vec!["State".into(), "Region".into()],
+            vec!["Purpose".into()],
+        );
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        let summary = hts.summary();
+        println!("{}", summary);
+
+        assert!(summary.n_series >= 1);
+        assert!(summary.n_bottom >= 1);
+    }
+
+    #[test]
+    fn test_to_long() {
+        let df = df! {
+            "Weight" => [1.0, 2.0, 3.0],
+            "Value" => [4.0, 5.0, 6.0],
+            "Purpose" => ["X", "Y", "X"],
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q2"],
+            "State" => ["A", "B", "A"],
+        }
+        .unwrap();
+        let spec = HierarchySpec::new(vec!["State".into()], vec!["Purpose".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec.clone(), "Quarter", "Value").unwrap();
+
+        let long = hts.to_long();
+        assert_eq!(
+            long.get_column_names(),
+            ["State", "Purpose", "Quarter", "Value"]
+        );
+        assert_eq!(long.height(), 3);
+        let aggregated = hts.aggregate_all().unwrap();
+        assert_eq!(long.get_column_names(), aggregated.get_column_names());
+
+        let rebuilt = HierarchicalTimeSeries::new(long, spec, "Quarter", "Value").unwrap();
+        assert!(rebuilt.aggregate_all().unwrap().equals_missing(&aggregated));
+    }
+
+    #[test]
+    fn test_new_with_diagnostics() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q2", "1998 Q1", "1998 Q1", "", "1998 Q2"],
+            "State" => ["A", "A", "A", "B", "B", "B"],
+            "Region" => ["AA", "AA", "AB", "BA", "BA", "BA"],
+            "Value" => [1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let config = HtsConfig {
+            na_period: NaPeriodPolicy::Drop,
+            ..Default::default()
+        };
+
+        let (hts, diagnostics) =
+            HierarchicalTimeSeries::new_with_diagnostics(df, spec, "Quarter", "Value", config)
+                .unwrap();
+        assert_eq!(diagnostics.n_rows, 5);
+        // Total, A, B, A/AA, A/AB, B/BA
+        assert_eq!(diagnostics.n_nodes, 6);
+        assert_eq!(diagnostics.n_edges, 5);
+        assert_eq!(hts.n_series(), diagnostics.n_nodes);
+        assert!(diagnostics.to_string().contains("Edges:          5"));
+    }
+
+    fn blank_period_df() -> DataFrame {
+        df! {
+            "Quarter" => [Some("1998 Q1"), Some("1998 Q2"), Some(""), None],
+            "State" => ["A", "A", "B", "B"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn test_na_period_policy() {
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+
+        // Default policy reports the offending row
+        let err = HierarchicalTimeSeries::new(blank_period_df(), spec.clone(), "Quarter", "Value")
+            .unwrap_err();
+        assert!(err.to_string().contains("row 2"), "{err}");
+
+        let drop = HtsConfig {
+            na_period: NaPeriodPolicy::Drop,
+            ..Default::default()
+        };
+        let hts = HierarchicalTimeSeries::with_config(
+            blank_period_df(),
+            spec.clone(),
+            "Quarter",
+            "Value",
+            drop,
+        )
+        .unwrap();
+        assert_eq!(hts.bottom_data().height(), 2);
+        assert_eq!(hts.n_periods(), 2);
+        assert_eq!(hts.n_bottom(), 1);
+
+        let keep = HtsConfig {
+            na_period: NaPeriodPolicy::Keep,
+            ..Default::default()
+        };
+        let hts =
+            HierarchicalTimeSeries::with_config(blank_period_df(), spec, "Quarter", "Value", keep)
+                .unwrap();
+        assert_eq!(hts.bottom_data().height(), 4);
+        assert_eq!(hts.n_periods(), 2);
+    }
+
+    #[test]
+    fn test_kept_blank_periods_are_not_aggregated() {
+        let df = df! {
+            "Quarter" => [Some("1998 Q1"), Some("1998 Q1"), Some(" "), None],
+            "State" => ["A", "B", "A", "B"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let keep = HtsConfig {
+            na_period: NaPeriodPolicy::Keep,
+            ..Default::default()
+        };
+        let hts = HierarchicalTimeSeries::with_config(df, spec, "Quarter", "Value", keep).unwrap();
+
+        // Blank and null times share one null key in the bottom data
+        assert_eq!(hts.bottom_data().column("Quarter").unwrap().null_count(), 2);
+
+        // ...and no period of the aggregates
+        let agg = hts.aggregate_all().unwrap();
+        assert_eq!(agg.height(), hts.n_series());
+        assert_eq!(agg.column("Quarter").unwrap().null_count(), 0);
+        assert_eq!(
+            hts.total_by_period().unwrap(),
+            [(Period::Quarterly(1998, 1), 3.0)]
+        );
+
+        let reconciled = hts.reconciled_frame(&agg, ReconMethod::Ols).unwrap();
+        assert_eq!(reconciled.height(), agg.height());
+
+        // External rows without a period are skipped, not parsed
+        let blank = agg
+            .head(Some(1))
+            .lazy()
+            .with_column(lit(NULL).cast(DataType::String).alias("Quarter"))
+            .collect()
+            .unwrap();
+        let external = agg.vstack(&blank).unwrap();
+        let report = hts
+            .reconcile_external(&external, Tolerance::default())
+            .unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.n_compared, agg.height());
+
+        let report = hts
+            .reconciliation_report(&external, &external, &external)
+            .unwrap();
+        assert_eq!(report.levels.len(), 2);
+        assert_eq!(report.levels[0].rmse_base, 0.0);
+    }
+
+    #[test]
+    fn test_ordinal_periods() {
+        let df = df! {
+            "t" => [10i64, 2, 1, 10],
+            "State" => ["A", "B", "A", "B"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+
+        let calendar = HierarchicalTimeSeries::new(df.clone(), spec.clone(), "t", "Value").unwrap();
+        assert_eq!(calendar.periods()[0], Period::Annual(1));
+
+        let config = HtsConfig {
+            period_format: PeriodFormat::Ordinal,
+            ..Default::default()
+        };
+        let hts = HierarchicalTimeSeries::with_config(df, spec, "t", "Value", config).unwrap();
+        assert_eq!(
+            hts.periods(),
+            &[Period::Ordinal(1), Period::Ordinal(2), Period::Ordinal(10)]
+        );
+    }
+
+    #[test]
+    fn test_distinct_values() {
+        let df = df! {
+            "Quarter" => ["1998 Q1"; 4],
+            "State" => ["B", "A", "B", "A"],
+            "Purpose" => ["Y", "X", "X", "Y"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::new(vec!["State".into()], vec!["Purpose".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        assert_eq!(hts.distinct_values("State").unwrap(), vec!["A", "B"]);
+        assert_eq!(hts.distinct_values("Purpose").unwrap(), vec!["X", "Y"]);
+        for node in hts.hierarchy_tree() {
+            if let Some(state) = node.labels.get("State") {
+                assert!(hts.distinct_values("State").unwrap().contains(state));
+            }
+        }
+        assert!(hts.distinct_values("Value").is_err());
+    }
+
+    #[test]
+    fn test_zero_series() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q2", "1998 Q1", "1998 Q2", "1998 Q1", "1998 Q2"],
+            "State" => ["A", "A", "A", "A", "B", "B"],
+            "Region" => ["AA", "AA", "AB", "AB", "BA", "BA"],
+            "Value" => [Some(0.0), None, Some(1.0), Some(0.0), Some(-2.0), Some(2.0)],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        assert_eq!(hts.zero_series().unwrap(), vec!["A/AA"]);
+
+        // Ids follow the collated bottom-level order
+        let df = df! {
+            "Quarter" => ["1998 Q1"; 3],
+            "Store" => ["10", "9", "2"],
+            "Value" => [0.0, 0.0, 1.0],
+        }
+        .unwrap();
+        let config = HtsConfig {
+            collation: Collation::Numeric,
+            ..Default::default()
+        };
+        let spec = HierarchySpec::hierarchical(vec!["Store".into()]);
+        let hts =
+            HierarchicalTimeSeries::with_config(df, spec, "Quarter", "Value", config).unwrap();
+        assert_eq!(hts.zero_series().unwrap(), vec!["9", "10"]);
+    }
+
+    #[test]
+    fn test_recon_context() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[2], 2, 1);
+        let ctx = hts.recon_context();
+
+        assert_eq!(ctx.levels().len(), hts.n_series());
+        assert_eq!(ctx.levels()[0], 0);
+        assert_eq!(ctx.row_labels(), hts.summation_matrix().row_labels());
+    }
+
+    #[test]
+    fn test_with_added_group() {
+        let df = df! {
+            "Quarter" => ["1998 Q1"; 4],
+            "State" => ["A", "A", "B", "B"],
+            "Purpose" => ["X", "Y", "X", "Y"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+        assert_eq!(hts.n_series(), 3);
+
+        let grouped = hts.with_added_group("Purpose").unwrap();
+        assert_eq!(grouped.spec().groups, vec!["Purpose"]);
+        assert_eq!(grouped.n_bottom(), 4);
+        assert_eq!(grouped.periods(), hts.periods());
+        assert!(grouped.hierarchy_tree().get_node("A/X").is_some());
+
+        assert!(matches!(
+            hts.with_added_group("Mode"),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+        assert!(hts.with_added_group("State").is_err());
+        assert!(hts.with_added_group("Value").is_err());
+    }
+
+    #[test]
+    fn test_subtree() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q2", "1998 Q1"],
+            "State" => ["A", "A", "A", "B"],
+            "City" => ["a1", "a2", "a1", "b1"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "City".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        let sub = hts.subtree("A").unwrap();
+        assert_eq!(sub.n_bottom(), 2);
+        assert_eq!(sub.n_series(), 4);
+        assert_eq!(sub.periods(), hts.periods());
+        let q1 = Period::Quarterly(1998, 1);
+        assert_eq!(sub.value_at("A", q1).unwrap(), Some(3.0));
+        assert_eq!(sub.value_at(DEFAULT_TOTAL_LABEL, q1).unwrap(), Some(3.0));
+        assert!(sub.value_at("B", q1).is_err());
+
+        let leaf = hts.subtree("A/a1").unwrap();
+        assert_eq!(leaf.n_bottom(), 1);
+        assert_eq!(
+            leaf.value_at("A/a1", Period::Quarterly(1998, 2)).unwrap(),
+            Some(3.0)
+        );
+
+        assert_eq!(
+            hts.subtree(DEFAULT_TOTAL_LABEL).unwrap().n_series(),
+            hts.n_series()
+        );
+        assert!(matches!(hts.subtree("C"), Err(HtsError::Hierarchy(_))));
+    }
+
+    #[test]
+    fn test_node_meta_survives_rebuilds() {
+        let df = df! {
+            "Quarter" => ["1998 Q1"; 4],
+            "State" => ["A", "A", "B", "B"],
+            "Purpose" => ["X", "Y", "X", "Y"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let mut hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+        assert!(hts.set_node_meta("A", "manager", "Ana"));
+        assert!(hts.set_node_meta(DEFAULT_TOTAL_LABEL, "manager", "Bia"));
+        assert!(!hts.set_node_meta("C", "manager", "Caio"));
+
+        let grouped = hts.with_added_group("Purpose").unwrap();
+        assert_eq!(
+            grouped.hierarchy_tree().node_meta("A", "manager"),
+            Some("Ana")
+        );
+
+        let sub = hts.subtree("A").unwrap();
+        assert_eq!(sub.hierarchy_tree().node_meta("A", "manager"), Some("Ana"));
+        assert_eq!(
+            sub.hierarchy_tree()
+                .node_meta(DEFAULT_TOTAL_LABEL, "manager"),
+            None
+        );
+
+        let mapping = HashMap::from([("A".to_string(), "AA".to_string())]);
+        hts.relabel("State", &mapping, false).unwrap();
+        let tree = hts.hierarchy_tree();
+        assert_eq!(tree.node_meta("AA", "manager"), Some("Ana"));
+        assert_eq!(tree.node_meta(DEFAULT_TOTAL_LABEL, "manager"), Some("Bia"));
+    }
+
+    #[test]
+    fn test_single_period() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[2], 1, 3);
+        assert_eq!(hts.n_periods(), 1);
+        assert_eq!(hts.periods(), &[Period::Quarterly(2000, 1)]);
+
+        let aggregated = hts.aggregate_all().unwrap();
+        assert_eq!(aggregated.height(), hts.n_series());
+
+        let s = hts.summation_matrix();
+        let y_hat: Vec<f64> = (0..s.n_series()).map(|i| i as f64).collect();
+        for method in [
+            ReconMethod::BottomUp,
+            ReconMethod::Ols,
+            ReconMethod::WlsStructural,
+        ] {
+            let y_tilde = s.reconcile(&y_hat, method).unwrap();
+            assert!(s.is_coherent(&y_tilde, Default::default()));
+        }
+    }
+
+    #[test]
+    fn test_unsorted_periods() {
+        let df = df! {
+            "Quarter" => ["1998 Q3", "1998 Q1", "1998 Q3", "1998 Q2"],
+            "State" => ["A", "A", "B", "B"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+
+        let sorted =
+            HierarchicalTimeSeries::new(df.clone(), spec.clone(), "Quarter", "Value").unwrap();
+        assert!(sorted.periods().is_sorted());
+
+        let config = HtsConfig {
+            sort_periods: false,
+            ..Default::default()
+        };
+        let hts =
+            HierarchicalTimeSeries::with_config(df, spec, "Quarter", "Value", config).unwrap();
+        assert_eq!(
+            hts.periods(),
+            &[
+                Period::Quarterly(1998, 3),
+                Period::Quarterly(1998, 1),
+                Period::Quarterly(1998, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sequential_aggregation() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[2], 4, 9);
+        let config = HtsConfig {
+            parallel: false,
+            ..Default::default()
+        };
+        let sequential = HierarchicalTimeSeries::with_config(
+            hts.bottom_data().clone(),
+            hts.spec().clone(),
+            crate::testing::TIME_COL,
+            crate::testing::VALUE_COL,
+            config,
+        )
+        .unwrap();
+
+        assert!(
+            sequential
+                .aggregate_all()
+                .unwrap()
+                .equals_missing(&hts.aggregate_all().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_time_or_value_column_in_spec() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q2"],
+            "State" => ["A", "A"],
+            "Value" => [1.0, 2.0],
+        }
+        .unwrap();
+
+        for column in ["Quarter", "Value"] {
+            let spec = HierarchySpec::hierarchical(vec!["State".into(), column.into()]);
+            let err =
+                HierarchicalTimeSeries::new(df.clone(), spec, "Quarter", "Value").unwrap_err();
+            assert!(matches!(err, HtsError::Hierarchy(_)), "{err}");
+            assert!(err.to_string().contains(column), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_seasonal_means() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q2", "1999 Q1", "1999 Q2"],
+            "State" => ["A", "A", "A", "A"],
+            "Value" => [1.0, 2.0, 3.0, 6.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec.clone(), "Quarter", "Value").unwrap();
+
+        let means = hts.seasonal_means().unwrap();
+        // Two series (Total and A) times two seasons
+        assert_eq!(means.height(), 4);
+        let season = means.column("season").unwrap().u32().unwrap();
+        let value = means.column("Value").unwrap().f64().unwrap();
+        assert_eq!((season.get(0), value.get(0)), (Some(1), Some(2.0)));
+        assert_eq!((season.get(1), value.get(1)), (Some(2), Some(4.0)));
+
+        let annual = df! {
+            "Year" => ["1998", "1999"],
+            "State" => ["A", "A"],
+            "Value" => [1.0, 2.0],
+        }
+        .unwrap();
+        let hts = HierarchicalTimeSeries::new(annual, spec, "Year", "Value").unwrap();
+        assert!(matches!(
+            hts.seasonal_means(),
+            Err(HtsError::InvalidPeriod(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_cells_and_strict_mode() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q2", "1998 Q2"],
+            "State" => ["A", "B", "A", "B"],
+            "Value" => [Some(1.0), Some(2.0), Some(3.0), None],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+
+        let lenient =
+            HierarchicalTimeSeries::new(df.clone(), spec.clone(), "Quarter", "Value").unwrap();
+        let missing = lenient.missing_cells().unwrap();
+        assert_eq!(missing.height(), 1);
+        assert_eq!(
+            missing.column("id").unwrap().str().unwrap().get(0),
+            Some("B")
+        );
+        assert_eq!(
+            missing.column("Quarter").unwrap().str().unwrap().get(0),
+            Some("1998 Q2")
+        );
+        assert!(lenient.aggregate_all().is_ok());
+
+        // The total inherits B's gap
+        let completeness = lenient.node_completeness().unwrap();
+        let ids = completeness.column("id").unwrap().str().unwrap();
+        let complete = completeness
+            .column("complete_periods")
+            .unwrap()
+            .u32()
+            .unwrap();
+        let by_id: HashMap<&str, u32> = ids
+            .into_no_null_iter()
+            .zip(complete.into_no_null_iter())
+            .collect();
+        assert_eq!(by_id, HashMap::from([("Total", 1), ("A", 2), ("B", 1)]));
+        let ratio = completeness.column("completeness").unwrap().f64().unwrap();
+        assert_eq!(ratio.get(0), Some(0.5));
+
+        let config = HtsConfig {
+            strict: true,
+            ..Default::default()
+        };
+        let strict =
+            HierarchicalTimeSeries::with_config(df, spec, "Quarter", "Value", config).unwrap();
+        let err = strict.aggregate_all().unwrap_err();
+        assert!(matches!(err, HtsError::MissingData(_)), "{err}");
+        assert!(err.to_string().contains("B @ 1998 Q2"), "{err}");
+        assert!(matches!(
+            strict.value_at("B", Period::Quarterly(1998, 2)),
+            Err(HtsError::MissingData(_))
+        ));
+        assert!(matches!(
+            strict.get_series("A"),
+            Err(HtsError::MissingData(_))
+        ));
+        assert_eq!(lenient.get_series("B").unwrap(), Some(vec![2.0, 0.0]));
+        assert_eq!(
+            lenient.value_at("B", Period::Quarterly(1998, 2)).unwrap(),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_pre_aggregate() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q1", "1998 Q2", "1998 Q2"],
+            "State" => ["A", "A", "B", "A", "B"],
+            "Store" => ["s1", "s2", "s3", "s1", "s3"],
+            "Value" => [Some(1.0), Some(3.0), Some(5.0), Some(2.0), None],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let build = |agg| {
+            let config = HtsConfig {
+                pre_aggregate: agg,
+                ..Default::default()
+            };
+            HierarchicalTimeSeries::with_config(
+                df.clone(),
+                spec.clone(),
+                "Quarter",
+                "Value",
+                config,
+            )
+            .unwrap()
+        };
+
+        let raw = build(None);
+        let summed = build(Some(AggregationFn::Sum));
+        assert_eq!(raw.bottom_data().height(), 5);
+        assert_eq!(summed.bottom_data().height(), 4);
+        assert_eq!(summed.bottom_data().width(), 3);
+        assert!(
+            summed
+                .aggregate_all()
+                .unwrap()
+                .equals_missing(&raw.aggregate_all().unwrap())
+        );
+        // An all-null group stays missing
+        assert_eq!(summed.missing_cells().unwrap().height(), 1);
+
+        let mean = build(Some(AggregationFn::Mean));
+        let values = mean
+            .bottom_data()
+            .column("Value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .clone();
+        assert_eq!(values.get(0), Some(2.0));
+        let last = build(Some(AggregationFn::Last));
+        let values = last
+            .bottom_data()
+            .column("Value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .clone();
+        assert_eq!(values.get(0), Some(3.0));
+
+        // Non-additive values are collapsed to their weighted mean
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q1", "1998 Q2"],
+            "State" => ["A", "A", "B", "A"],
+            "Store" => ["s1", "s2", "s3", "s1"],
+            "Rate" => [Some(0.1), Some(0.4), Some(0.3), None],
+            "Labor" => [300.0, 100.0, 50.0, 20.0],
+        }
+        .unwrap();
+        let build = |agg| {
+            let config = HtsConfig {
+                value_semantics: ValueKind::Ratio,
+                weight_col: Some("Labor".into()),
+                pre_aggregate: agg,
+                ..Default::default()
+            };
+            HierarchicalTimeSeries::with_config(df.clone(), spec.clone(), "Quarter", "Rate", config)
+                .unwrap()
+        };
+        let raw = build(None);
+        let collapsed = build(Some(AggregationFn::Mean));
+        let tol = Tolerance::default();
+        let rates = collapsed
+            .bottom_data()
+            .column("Rate")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .clone();
+        assert!(tol.approx_eq(rates.get(0).unwrap(), (0.1 * 300.0 + 0.4 * 100.0) / 400.0));
+        assert_eq!(rates.get(2), None);
+        let weights = collapsed
+            .bottom_data()
+            .column("Labor")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .clone();
+        assert_eq!(weights.get(0), Some(400.0));
+
+        let (expected, collapsed) = (
+            raw.aggregate_all().unwrap(),
+            collapsed.aggregate_all().unwrap(),
+        );
+        let (expected, collapsed) = (
+            expected.column("Rate").unwrap().f64().unwrap().clone(),
+            collapsed.column("Rate").unwrap().f64().unwrap().clone(),
+        );
+        for (a, b) in expected.into_iter().zip(&collapsed) {
+            match (a, b) {
+                (Some(a), Some(b)) if a.is_nan() => assert!(b.is_nan()),
+                (Some(a), Some(b)) => assert!(tol.approx_eq(a, b)),
+                (a, b) => assert_eq!(a, b),
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_additive_values() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q2", "1998 Q2"],
+            "State" => ["A", "B", "A", "B"],
+            "Rate" => [0.1, 0.4, 0.2, 0.2],
+            "Labor" => [300.0, 100.0, 50.0, 50.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let build = |weight_col: Option<&str>| {
+            let config = HtsConfig {
+                value_semantics: ValueKind::Ratio,
+                weight_col: weight_col.map(String::from),
+                ..Default::default()
+            };
+            HierarchicalTimeSeries::with_config(df.clone(), spec.clone(), "Quarter", "Rate", config)
+        };
+
+        let unweighted = build(None).unwrap();
+        assert!(matches!(
+            unweighted.aggregate_all(),
+            Err(HtsError::NonAdditive(_))
+        ));
+        assert!(matches!(
+            unweighted.total_by_period(),
+            Err(HtsError::NonAdditive(_))
+        ));
+        assert!(matches!(
+            build(Some("Workers")),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+
+        let weighted = build(Some("Labor")).unwrap();
+        let tol = Tolerance::default();
+        let totals = weighted.total_by_period().unwrap();
+        assert!(tol.approx_eq(totals[0].1, (0.1 * 300.0 + 0.4 * 100.0) / 400.0));
+        assert!(tol.approx_eq(totals[1].1, 0.2));
+
+        let all = weighted.aggregate_all().unwrap();
+        let rates = all.column("Rate").unwrap().f64().unwrap();
+        assert!(tol.approx_eq(rates.get(0).unwrap(), totals[0].1));
+        // Bottom-level values are unchanged
+        assert_eq!(rates.get(2), Some(0.1));
+    }
+
+    #[test]
+    fn test_time_varying_weights() {
+        // Population shifts from A to B between the two years
+        let df = df! {
+            "Year" => [2000i64, 2000, 2000, 2001, 2001, 2001],
+            "State" => ["N", "N", "S", "N", "N", "S"],
+            "Region" => ["A", "B", "C", "A", "B", "C"],
+            "PerCapita" => [10.0, 20.0, 30.0, 10.0, 20.0, 30.0],
+            "Population" => [3.0, 1.0, 4.0, 1.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let config = HtsConfig {
+            value_semantics: ValueKind::Ratio,
+            weight_col: Some("Population".into()),
+            ..Default::default()
+        };
+        let hts =
+            HierarchicalTimeSeries::with_config(df, spec, "Year", "PerCapita", config).unwrap();
+
+        let all = hts.aggregate_all().unwrap();
+        let value = |state: &str, region: &str, year: &str| {
+            let mask = all.column("State").unwrap().str().unwrap().equal(state)
+                & all.column("Region").unwrap().str().unwrap().equal(region)
+                & all.column("Year").unwrap().str().unwrap().equal(year);
+            let rows = all.filter(&mask).unwrap();
+            assert_eq!(rows.height(), 1);
+            rows.column("PerCapita")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .get(0)
+                .unwrap()
+        };
+        let tol = Tolerance::default();
+        assert!(tol.approx_eq(value("N", AGGREGATED_LABEL, "2000"), 12.5));
+        assert!(tol.approx_eq(value("N", AGGREGATED_LABEL, "2001"), 17.5));
+        assert!(tol.approx_eq(
+            value(AGGREGATED_LABEL, AGGREGATED_LABEL, "2000"),
+            (30.0 + 20.0 + 120.0) / 8.0
+        ));
+        assert!(tol.approx_eq(
+            value(AGGREGATED_LABEL, AGGREGATED_LABEL, "2001"),
+            (10.0 + 60.0 + 120.0) / 8.0
+        ));
+
+        // Weighted means are not sums, so S-based reconciliation is refused
+        assert!(matches!(
+            hts.reconciled_frame(&all, ReconMethod::Ols),
+            Err(HtsError::NonAdditive(_))
+        ));
+    }
+
+    #[test]
+    fn test_values_f64() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1"],
+            "State" => ["A", "B"],
+            "Trips" => [Some(3i64), None],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Trips").unwrap();
+
+        let values = hts.values_f64().unwrap();
+        assert_eq!(values.get(0), Some(3.0));
+        assert_eq!(values.get(1), None);
+
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1"],
+            "State" => ["A", "B"],
+            "Trips" => ["abc", "1.5"],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Trips").unwrap();
+        assert!(hts.values_f64().is_err());
+    }
+
+    #[test]
+    fn test_from_aggregated() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[], 2, 3);
+        let (time_col, value_col) = (crate::testing::TIME_COL, crate::testing::VALUE_COL);
+        let all = hts.aggregate_all().unwrap();
+
+        let rebuilt = HierarchicalTimeSeries::from_aggregated(
+            all.clone(),
+            hts.spec().clone(),
+            time_col,
+            value_col,
+            AGGREGATED_LABEL,
+        )
+        .unwrap();
+        assert!(rebuilt.aggregate_all().unwrap().equals_missing(&all));
+
+        // Another tool's placeholder
+        let key_cols: Vec<String> = hts
+            .spec()
+            .all_columns()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let mut other = all.clone();
+        for name in &key_cols {
+            let relabeled: StringChunked =
+                other
+                    .column(name)
+                    .unwrap()
+                    .str()
+                    .unwrap()
+                    .apply_values(|v| {
+                        if v == AGGREGATED_LABEL {
+                            "*".into()
+                        } else {
+                            v.to_string().into()
+                        }
+                    });
+            other
+                .with_column(relabeled.with_name(name.as_str().into()).into_column())
+                .unwrap();
+        }
+        let rebuilt = HierarchicalTimeSeries::from_aggregated(
+            other.clone(),
+            hts.spec().clone(),
+            time_col,
+            value_col,
+            "*",
+        )
+        .unwrap();
+        assert_eq!(rebuilt.n_series(), hts.n_series());
+
+        // The total of the first period no longer adds up
+        let mut values: Vec<Option<f64>> = other
+            .column(value_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        values[0] = values[0].map(|v| v + 1.0);
+        other
+            .with_column(Column::new(value_col.into(), values))
+            .unwrap();
+        assert!(matches!(
+            HierarchicalTimeSeries::from_aggregated(
+                other,
+                hts.spec().clone(),
+                time_col,
+                value_col,
+                "*"
+            ),
+            Err(HtsError::Reconciliation(_))
+        ));
+    }
+
+    #[test]
+    fn test_reconcile_external() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[], 2, 3);
+        let value_col = crate::testing::VALUE_COL;
+        let own = hts.aggregate_all().unwrap();
+
+        let report = hts.reconcile_external(&own, Tolerance::default()).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.n_compared, own.height());
+
+        // Perturb the first row, the total in the first period
+        let mut values: Vec<Option<f64>> = own
+            .column(value_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        values[0] = values[0].map(|v| v + 1.0);
+        let mut external = own.clone();
+        external
+            .with_column(Column::new(value_col.into(), values))
+            .unwrap();
+
+        let report = hts
+            .reconcile_external(&external, Tolerance::default())
+            .unwrap();
+        assert_eq!(report.mismatches.len(), 1);
+        let diff = &report.mismatches[0];
+        assert_eq!(diff.id, "Total");
+        assert_eq!(diff.actual, Some(diff.expected + 1.0));
+
+        let unknown = own
+            .head(Some(1))
+            .lazy()
+            .with_column(lit("Nowhere").alias("Level1"));
+        let report = hts
+            .reconcile_external(&unknown.collect().unwrap(), Tolerance::default())
+            .unwrap();
+        assert_eq!(report.unmatched.len(), 1);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_new_validated() {
+        let df = df! {
+            "Quarter" => [Some("1998 Q1"), None, Some("1998 Q5"), Some("soon"), Some("soon")],
+            "State" => ["A/B", "A", "A", "B", "C"],
+            "Region" => ["C", "B/C", "AA", "BA", "CA"],
+            "Value" => [1.0, 2.0, 3.0, 4.0, 5.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let build = |df: DataFrame, spec: HierarchySpec, config: HtsConfig| {
+            HierarchicalTimeSeries::new_validated(df, spec, "Quarter", "Value", config)
+        };
+
+        // A blank period, two bad ones and a clashing id, all at once
+        let errors = build(df.clone(), spec.clone(), HtsConfig::default()).unwrap_err();
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(matches!(&errors[0], HtsError::InvalidPeriod(msg) if msg.contains("row 1")));
+        assert!(matches!(&errors[1], HtsError::InvalidPeriod(msg) if msg.contains("row 2")));
+        assert!(matches!(&errors[2], HtsError::InvalidPeriod(msg) if msg.contains("row 3")));
+        assert!(matches!(&errors[3], HtsError::Hierarchy(msg) if msg.contains("'A/B/C'")));
+
+        // Missing and repeated columns are reported together
+        let bad_spec = HierarchySpec::new(
+            vec!["State".into(), "City".into(), "State".into()],
+            vec!["Purpose".into()],
+        );
+        let config = HtsConfig {
+            na_period: NaPeriodPolicy::Drop,
+            ..Default::default()
+        };
+        let errors = build(df.clone(), bad_spec, config.clone()).unwrap_err();
+        assert!(matches!(&errors[0], HtsError::Hierarchy(msg) if msg.contains("'State'")));
+        assert!(matches!(&errors[1], HtsError::ColumnNotFound(c) if c == "City"));
+        assert!(matches!(&errors[2], HtsError::ColumnNotFound(c) if c == "Purpose"));
+        assert_eq!(errors.len(), 5, "{errors:?}");
+
+        // Clean input builds as with_config does
+        let clean = df! {
+            "Quarter" => ["1998 Q1", "1998 Q2"],
+            "State" => ["A", "B"],
+            "Region" => ["AA", "BA"],
+            "Value" => [1.0, 2.0],
+        }
+        .unwrap();
+        let hts = build(clean, spec, config).unwrap();
+        assert_eq!(hts.n_series(), 5);
+    }
+
+    #[test]
+    fn test_null_values() {
+        let dir = std::env::temp_dir().join(format!("hts_null_values_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("r_export.csv");
+        std::fs::write(
+            &path,
+            "Quarter,State,Value\n1998 Q1,A,1.5\n1998 Q1,B,NA\nNA,A,2\n1998 Q2,B,.\n",
+        )
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+
+        // "NA" is read as text by default
+        assert!(HierarchicalTimeSeries::from_csv(&path, spec.clone(), "Quarter", "Value").is_err());
+
+        let config = HtsConfig {
+            null_values: vec!["NA".into(), ".".into()],
+            na_period: NaPeriodPolicy::Drop,
+            ..Default::default()
+        };
+        let hts =
+            HierarchicalTimeSeries::from_csv_with_config(&path, spec, "Quarter", "Value", config)
+                .unwrap();
+        assert_eq!(hts.bottom_data().height(), 3);
+        assert_eq!(hts.n_periods(), 2);
+        let values = hts.values_f64().unwrap();
+        assert_eq!(
+            values.into_iter().collect::<Vec<_>>(),
+            [Some(1.5), None, None]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_csv_memory_map_toggle() {
+        let dir = std::env::temp_dir().join(format!("hts_memory_map_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tourism.csv");
+        std::fs::write(
+            &path,
+            "Quarter,State,Value\n1998 Q1,A,1.5\n1998 Q1,B,2\n1998 Q2,A,3\n1998 Q2,B,4\n",
+        )
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
 
-        // This is a simplified implementation
-        // Full version would extract and aggregate data by time period
-        Some(vec![0.0; self.n_periods()])
+        let load = |memory_map| {
+            let config = HtsConfig {
+                csv: crate::CsvImportOptions { memory_map },
+                ..Default::default()
+            };
+            HierarchicalTimeSeries::from_csv_with_config(
+                &path,
+                spec.clone(),
+                "Quarter",
+                "Value",
+                config,
+            )
+            .unwrap()
+            .aggregate_all()
+            .unwrap()
+        };
+        let mapped = load(true);
+        assert_eq!(mapped.height(), 6);
+        assert!(mapped.equals_missing(&load(false)));
+
+        let missing = HtsConfig {
+            csv: crate::CsvImportOptions { memory_map: false },
+            ..Default::default()
+        };
+        assert!(
+            HierarchicalTimeSeries::from_csv_with_config(
+                dir.join("missing.csv"),
+                spec,
+                "Quarter",
+                "Value",
+                missing,
+            )
+            .is_err()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    /// Returns a summary of the hierarchical structure.
-    pub fn summary(&self) -> HtsSummary {
-        HtsSummary {
-            n_series: self.n_series(),
-            n_bottom: self.n_bottom(),
-            n_periods: self.n_periods(),
-            hierarchy_cols: self.spec.hierarchy.clone(),
-            group_cols: self.spec.groups.clone(),
-            s_matrix_shape: self.s_matrix.shape(),
+    #[test]
+    fn test_date_time_column() {
+        let dates = ["2024-01-02", "2024-01-01", "2024-01-02"]
+            .map(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap());
+        let df = df! {
+            "Day" => dates,
+            "State" => ["A", "A", "B"],
+            "Value" => [1.0, 2.0, 3.0],
+        }
+        .unwrap();
+        assert_eq!(df.column("Day").unwrap().dtype(), &DataType::Date);
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Day", "Value").unwrap();
+        assert_eq!(
+            hts.periods(),
+            &[Period::Daily(dates[1]), Period::Daily(dates[0]),]
+        );
+        assert_eq!(hts.missing_cells().unwrap().height(), 1);
+
+        // Datetimes become daily periods, usable by every later method
+        let df = df! {
+            "Day" => dates.map(|d| d.and_hms_opt(12, 30, 0).unwrap()),
+            "State" => ["A", "A", "B"],
+            "Value" => [1.0, 2.0, 3.0],
         }
+        .unwrap();
+        assert!(matches!(
+            df.column("Day").unwrap().dtype(),
+            DataType::Datetime(..)
+        ));
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Day", "Value").unwrap();
+        assert_eq!(
+            hts.periods(),
+            &[Period::Daily(dates[1]), Period::Daily(dates[0])]
+        );
+        assert_eq!(hts.missing_cells().unwrap().height(), 1);
+        assert_eq!(
+            hts.total_by_period().unwrap(),
+            vec![
+                (Period::Daily(dates[1]), 2.0),
+                (Period::Daily(dates[0]), 4.0)
+            ]
+        );
+        assert_eq!(
+            hts.value_at("A", Period::Daily(dates[0])).unwrap(),
+            Some(1.0)
+        );
+        let aggregated = hts.aggregate_all().unwrap();
+        let report = hts
+            .reconcile_external(&aggregated, Tolerance::default())
+            .unwrap();
+        assert!(report.is_clean());
+        assert_eq!(hts.node_completeness().unwrap().height(), hts.n_series());
     }
-}
 
-/// Summary of a hierarchical time series structure.
-#[derive(Debug, Clone)]
-pub struct HtsSummary {
-    /// Total number of series.
-    pub n_series: usize,
-    /// Number of bottom-level series.
-    pub n_bottom: usize,
-    /// Number of time periods.
-    pub n_periods: usize,
-    /// Hierarchical columns.
-    pub hierarchy_cols: Vec<String>,
-    /// Grouped columns.
-    pub group_cols: Vec<String>,
-    /// Shape of S matrix (n, m).
-    pub s_matrix_shape: (usize, usize),
-}
+    #[test]
+    fn test_total_by_period() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[2], 3, 5);
+        let totals = hts.total_by_period().unwrap();
+        assert_eq!(totals.len(), hts.n_periods());
 
-impl std::fmt::Display for HtsSummary {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Hierarchical Time Series Summary")?;
-        writeln!(f, "================================")?;
-        writeln!(f, "Total series:  {}", self.n_series)?;
-        writeln!(f, "Bottom series: {}", self.n_bottom)?;
-        writeln!(f, "Time periods:  {}", self.n_periods)?;
-        writeln!(f, "Hierarchy:     {:?}", self.hierarchy_cols)?;
-        writeln!(f, "Groups:        {:?}", self.group_cols)?;
-        writeln!(
-            f,
-            "S matrix:      {} × {}",
-            self.s_matrix_shape.0, self.s_matrix_shape.1
-        )?;
-        Ok(())
+        let all = hts.aggregate_all().unwrap();
+        let values = all
+            .column(crate::testing::VALUE_COL)
+            .unwrap()
+            .f64()
+            .unwrap();
+        // The first rows of aggregate_all are the total, one per period
+        for (t, (period, total)) in totals.iter().enumerate() {
+            assert_eq!(*period, hts.periods()[t]);
+            assert!(Tolerance::default().approx_eq(*total, values.get(t).unwrap()));
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_integer_time_column() {
+        let df = df! {
+            "Year" => [2023i64, 2023, 2024, 2024],
+            "State" => ["A", "B", "A", "B"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df.clone(), spec.clone(), "Year", "Value").unwrap();
+
+        assert_eq!(hts.periods(), &[Period::Annual(2023), Period::Annual(2024)]);
+        assert_eq!(
+            hts.bottom_data().column("Year").unwrap().dtype(),
+            &DataType::String
+        );
+
+        // Same results as with a string column
+        let strings = df
+            .lazy()
+            .with_column(col("Year").cast(DataType::String))
+            .collect()
+            .unwrap();
+        let expected = HierarchicalTimeSeries::new(strings, spec, "Year", "Value").unwrap();
+        let all = hts.aggregate_all().unwrap();
+        assert!(all.equals_missing(&expected.aggregate_all().unwrap()));
+        assert!(
+            hts.reconcile_external(&all, Tolerance::default())
+                .unwrap()
+                .is_clean()
+        );
+        assert_eq!(
+            hts.total_by_period().unwrap(),
+            [(Period::Annual(2023), 3.0), (Period::Annual(2024), 7.0)]
+        );
+        assert!(hts.missing_cells().unwrap().height() == 0);
+        assert!(
+            hts.reconciled_frame(&all, ReconMethod::BottomUp)
+                .unwrap()
+                .equals_missing(&all)
+        );
+    }
 
     #[test]
-    fn test_hts_from_dataframe() {
+    fn test_reconciliation_report() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[], 8, 2);
+        let value_col = crate::testing::VALUE_COL;
+        let actual = hts.aggregate_all().unwrap();
+
+        // Incoherent base forecasts: the actuals with the total inflated
+        let mut values: Vec<Option<f64>> = actual
+            .column(value_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        for v in values.iter_mut().take(hts.n_periods()) {
+            *v = v.map(|v| v + 40.0);
+        }
+        let mut base = actual.clone();
+        base.with_column(Column::new(value_col.into(), values))
+            .unwrap();
+        let reconciled = hts.reconciled_frame(&base, ReconMethod::Ols).unwrap();
+
+        let report = hts
+            .reconciliation_report(&base, &reconciled, &actual)
+            .unwrap();
+        assert_eq!(report.levels.len(), 3);
+
+        let total = &report.levels[0];
+        assert_eq!(total.level, "Total");
+        assert!((total.rmse_base - 40.0).abs() < 1e-9);
+        // OLS spreads the error over the hierarchy, improving the total
+        assert!(total.rmse_reconciled < total.rmse_base);
+        assert!(total.rmse_improvement() > 0.0);
+
+        let bottom = &report.levels[2];
+        assert_eq!(bottom.n_series, hts.n_bottom());
+        assert_eq!(bottom.rmse_base, 0.0);
+        // Nothing to improve on an exact base forecast
+        assert!(bottom.rmse_improvement().is_nan());
+        assert!(bottom.mase_reconciled > 0.0);
+
+        let table = report.to_string();
+        assert!(table.contains("Level1/Level2"), "{table}");
+
+        let partial = base.head(Some(1));
+        assert!(matches!(
+            hts.reconciliation_report(&partial, &reconciled, &actual),
+            Err(HtsError::Reconciliation(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_aggregated_csv() {
         let df = df! {
-            "Quarter" => ["1998 Q1", "1998 Q2", "1998 Q1", "1998 Q2"],
+            "Quarter" => ["1998 Q1", "1998 Q1"],
+            "State" => ["A", "B"],
+            "Value" => [1.0 / 3.0, 2.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        let mut out = Vec::new();
+        hts.write_aggregated_csv(&mut out, Some(2)).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv,
+            "State,Quarter,Value\n<aggregated>,1998 Q1,2.33\nA,1998 Q1,0.33\nB,1998 Q1,2.00\n"
+        );
+
+        let mut out = Vec::new();
+        hts.write_aggregated_csv(&mut out, None).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("0.333333"));
+    }
+
+    #[cfg(feature = "ipc")]
+    #[test]
+    fn test_aggregate_to_ipc() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[2], 4, 3);
+
+        let mut out = Vec::new();
+        hts.aggregate_to_ipc(&mut out).unwrap();
+        let back = IpcStreamReader::new(std::io::Cursor::new(out))
+            .finish()
+            .unwrap();
+        assert!(back.equals_missing(&hts.aggregate_all().unwrap()));
+    }
+
+    #[test]
+    fn test_aggregate_levels() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[], 2, 8);
+        let all = hts.aggregate_all().unwrap();
+
+        // Total and middle level, leaving out the bottom
+        let upper = hts.aggregate_levels(&[1, 0]).unwrap();
+        let n_upper = (1 + 2) * hts.n_periods();
+        assert!(upper.equals_missing(&all.head(Some(n_upper))));
+
+        let everything = hts.aggregate_levels(&[0, 1, 2]).unwrap();
+        assert!(everything.equals_missing(&all));
+
+        assert!(matches!(
+            hts.aggregate_levels(&[3]),
+            Err(HtsError::Hierarchy(_))
+        ));
+    }
+
+    #[test]
+    fn test_relabel() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q1", "1998 Q1"],
+            "State" => ["A", "A", "Bee", "Bee"],
+            "Region" => ["A1", "A2", "B1", "B2"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let mut hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
+
+        let typo = HashMap::from([("Bee".to_string(), "B".to_string())]);
+        hts.relabel("State", &typo, false).unwrap();
+        assert!(hts.hierarchy_tree().contains("B/B1"));
+        assert!(!hts.hierarchy_tree().contains("Bee"));
+        assert_eq!(hts.n_series(), 7);
+
+        // Merging regions is rejected unless allowed
+        let merge = HashMap::from([("A2".to_string(), "A1".to_string())]);
+        let err = hts.relabel("Region", &merge, false).unwrap_err();
+        assert!(err.to_string().contains("A/A1, A/A2 -> A/A1"), "{err}");
+        assert_eq!(hts.n_bottom(), 4);
+
+        hts.relabel("Region", &merge, true).unwrap();
+        assert_eq!(hts.n_bottom(), 3);
+
+        assert!(matches!(
+            hts.relabel("Value", &merge, true),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_all_with_row_index() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[2], 3, 7);
+        let (time_col, value_col) = (crate::testing::TIME_COL, crate::testing::VALUE_COL);
+        let df = hts.aggregate_all_with_row_index().unwrap();
+        let plain = hts.aggregate_all().unwrap();
+        assert_eq!(df.height(), plain.height());
+
+        let index = df.column("s_row_index").unwrap().u32().unwrap();
+        let values = df.column(value_col).unwrap().f64().unwrap();
+        let periods = df.column(time_col).unwrap().str().unwrap();
+
+        // Values scattered into S row order must be coherent in every period
+        for period in hts.periods() {
+            let label = period.to_string();
+            let mut y = vec![0.0; hts.n_series()];
+            for r in 0..df.height() {
+                if periods.get(r) == Some(label.as_str()) {
+                    y[index.get(r).unwrap() as usize] = values.get(r).unwrap();
+                }
+            }
+            assert!(hts.summation_matrix().is_coherent(&y, Tolerance::default()));
+        }
+
+        let mut seen: Vec<u32> = index.into_no_null_iter().collect();
+        seen.sort_unstable();
+        seen.dedup();
+        assert_eq!(seen.len(), hts.n_series());
+    }
+
+    #[test]
+    fn test_aggregate_all_rollup_matches_direct() {
+        let hts = crate::testing::random_hierarchy(&[3, 2], &[2], 4, 11);
+        let value_col = crate::testing::VALUE_COL;
+
+        // The custom-expression path groups the raw rows at every level
+        let direct = hts.aggregate_all_expr(col(value_col).sum()).unwrap();
+        let rolled = hts.aggregate_all().unwrap();
+        assert!(rolled.equals_missing(&direct));
+
+        let counts = hts.aggregate_all_with_counts().unwrap();
+        let count = counts.column("count").unwrap();
+        assert_eq!(count.dtype(), &IDX_DTYPE);
+        assert_eq!(
+            count.get(0).unwrap(),
+            AnyValue::from(hts.n_bottom() as IdxSize)
+        );
+    }
+
+    #[test]
+    fn test_top_down_proportions() {
+        let df = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q2", "1998 Q2"],
+            "State" => ["A", "B", "A", "B"],
+            "Value" => [1.0, 3.0, 6.0, 2.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let hts = HierarchicalTimeSeries::new(df, spec.clone(), "Quarter", "Value").unwrap();
+
+        let proportions = |method| {
+            let df = hts.top_down_proportions(method).unwrap();
+            assert_eq!(df.column("id").unwrap().str().unwrap().get(0), Some("A"));
+            df.column("proportion")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>()
+        };
+
+        // Shares of A: 1/4 and 6/8
+        let average = proportions(TopDownMethod::AverageHistoricalProportions);
+        assert!((average[0] - 0.5).abs() < 1e-12);
+        assert!((average[1] - 0.5).abs() < 1e-12);
+
+        // Means: A = 3.5, B = 2.5, total = 6
+        let of_averages = proportions(TopDownMethod::ProportionsOfHistoricalAverages);
+        assert!((of_averages[0] - 7.0 / 12.0).abs() < 1e-12);
+        assert!((of_averages[1] - 5.0 / 12.0).abs() < 1e-12);
+
+        let zeros = df! {
+            "Quarter" => ["1998 Q1", "1998 Q1"],
+            "State" => ["A", "B"],
+            "Value" => [0.0, 0.0],
+        }
+        .unwrap();
+        let hts = HierarchicalTimeSeries::new(zeros, spec, "Quarter", "Value").unwrap();
+        assert!(matches!(
+            hts.top_down_proportions(TopDownMethod::ProportionsOfHistoricalAverages),
+            Err(HtsError::Reconciliation(_))
+        ));
+
+        // The total splits fine, but state B has nothing to split among its
+        // regions
+        let zero_state = df! {
+            "Quarter" => ["1998 Q1"; 4],
             "State" => ["A", "A", "B", "B"],
-            "Region" => ["AA", "AA", "BA", "BA"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+            "Value" => [1.0, 3.0, 0.0, 0.0],
+        }
+        .unwrap();
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let hts = HierarchicalTimeSeries::new(zero_state, spec, "Quarter", "Value").unwrap();
+        for method in [
+            TopDownMethod::AverageHistoricalProportions,
+            TopDownMethod::ProportionsOfHistoricalAverages,
+        ] {
+            let err = hts.top_down_proportions(method).unwrap_err();
+            assert!(matches!(err, HtsError::Reconciliation(_)));
+            assert!(err.to_string().contains("'B'"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_value_at() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[2], 4, 5);
+        let (ids, periods, values) = hts.frame_cells(&hts.aggregate_all().unwrap()).unwrap();
+        for ((id, period), value) in ids.iter().zip(&periods).zip(&values) {
+            let at = hts.value_at(id, *period).unwrap().unwrap();
+            assert!((at - value.unwrap()).abs() < 1e-9, "{id} @ {period}");
+        }
+
+        assert_eq!(hts.value_at(&ids[0], Period::Ordinal(-1)).unwrap(), None);
+
+        for (id, period) in ids.iter().zip(&periods).step_by(7) {
+            let t = hts.periods().iter().position(|p| p == period).unwrap();
+            let series = hts.get_series(id).unwrap().unwrap();
+            assert_eq!(series.len(), hts.n_periods());
+            assert_eq!(Some(series[t]), hts.value_at(id, *period).unwrap());
+        }
+        assert_eq!(hts.get_series("no/such/series").unwrap(), None);
+        assert!(matches!(
+            hts.value_at("no/such/series", periods[0]),
+            Err(HtsError::Hierarchy(_))
+        ));
+    }
+
+    #[test]
+    fn test_reconcile_frame() {
+        let df = df! {
+            "Quarter" => ["1998 Q1"; 4],
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
             "Value" => [1.0, 2.0, 3.0, 4.0],
         }
         .unwrap();
@@ -346,34 +4406,412 @@ mod tests {
         let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
         let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
 
-        let summary = hts.summary();
-        println!("{}", summary);
+        // Rows in arbitrary order
+        let base = df! {
+            "id" => ["B/BB", "Total", "A", "B", "A/AA", "A/AB", "B/BA"],
+            "fc" => [4.0, 11.0, 2.5, 7.0, 1.0, 2.0, 3.0],
+        }
+        .unwrap();
 
-        assert_eq!(hts.n_periods(), 2);
-        assert!(hts.n_series() >= 2);
+        let out = hts
+            .reconcile_frame(ReconMethod::BottomUp, &base, "id", "fc")
+            .unwrap();
+        let ids: Vec<&str> = out
+            .column("id")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(ids, hts.summation_matrix().row_labels());
+        let total = out.column("fc").unwrap().f64().unwrap().get(0).unwrap();
+        assert_eq!(total, 10.0);
+
+        // Missing ids are all reported
+        let partial = base.slice(2, 5);
+        let err = hts
+            .reconcile_frame(ReconMethod::Ols, &partial, "id", "fc")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Total") && err.contains("B/BB"), "{err}");
     }
 
     #[test]
-    fn test_hts_summary() {
+    fn test_reconcile_all_to_frame() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[], 2, 3);
+        let value_col = crate::testing::VALUE_COL;
+        let aggregated = hts.aggregate_all().unwrap();
+        let base = aggregated
+            .clone()
+            .lazy()
+            .with_column((col(value_col) * lit(1.5) + lit(1.0)).alias("Other"))
+            .collect()
+            .unwrap();
+        let methods = HashMap::from([
+            (value_col.to_string(), ReconMethod::Ols),
+            ("Other".to_string(), ReconMethod::WlsStructural),
+        ]);
+
+        let out = hts.reconcile_all_to_frame(&base, &methods).unwrap();
+        assert_eq!(
+            out.get_column_names(),
+            ["id", "level", crate::testing::TIME_COL, "Other", value_col]
+        );
+        assert_eq!(out.height(), hts.n_series() * hts.n_periods());
+
+        let n = hts.n_series();
+        let ids = out.column("id").unwrap().str().unwrap();
+        let levels = out.column("level").unwrap().u32().unwrap();
+        assert_eq!(
+            ids.get(0),
+            Some(hts.summation_matrix().row_labels()[0].as_str())
+        );
+        assert_eq!(ids.get(n), ids.get(0));
+        assert_eq!((levels.get(0), levels.get(n - 1)), (Some(0), Some(2)));
+
+        // Each quantity matches reconciling it on its own
+        for (name, method) in &methods {
+            let values: Vec<f64> = out
+                .column(name)
+                .unwrap()
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect();
+            let mut single = base.clone();
+            single
+                .with_column(
+                    base.column(name)
+                        .unwrap()
+                        .clone()
+                        .with_name(value_col.into()),
+                )
+                .unwrap();
+            let result = hts.reconcile_periods(&single, value_col, *method).unwrap();
+            assert_eq!(values, result.values.concat());
+            assert!(
+                hts.summation_matrix()
+                    .is_coherent(&values[..n], Tolerance::default())
+            );
+        }
+
+        assert!(hts.reconcile_all_to_frame(&base, &HashMap::new()).is_err());
+        let missing = HashMap::from([("Nights".to_string(), ReconMethod::Ols)]);
+        assert!(matches!(
+            hts.reconcile_all_to_frame(&base, &missing),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_reconciled_frame_matches_aggregate_all_layout() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[2], 3, 5);
+        let aggregated = hts.aggregate_all().unwrap();
+        let value_col = crate::testing::VALUE_COL;
+        let actual: Vec<f64> = aggregated
+            .column(value_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+
+        // Perturb the first row (the total in the first period)
+        let mut perturbed = actual.clone();
+        perturbed[0] += 100.0;
+        let mut base = aggregated.clone();
+        base.with_column(Column::new(value_col.into(), perturbed))
+            .unwrap();
+
+        let out = hts.reconciled_frame(&base, ReconMethod::BottomUp).unwrap();
+        assert_eq!(out.schema(), aggregated.schema());
+        assert_eq!(out.height(), aggregated.height());
+        for name in hts.spec().all_columns() {
+            assert!(
+                out.column(name)
+                    .unwrap()
+                    .equals(aggregated.column(name).unwrap())
+            );
+        }
+        let reconciled: Vec<f64> = out
+            .column(value_col)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        for (a, b) in reconciled.iter().zip(&actual) {
+            assert!((a - b).abs() < 1e-9);
+        }
+
+        // A period missing one series is rejected
+        let incomplete = aggregated.slice(0, aggregated.height() - 1);
+        let err = hts
+            .reconciled_frame(&incomplete, ReconMethod::Ols)
+            .unwrap_err();
+        assert!(err.to_string().contains("No base forecast"), "{err}");
+    }
+
+    #[test]
+    fn test_aggregate_all_with_counts() {
         let df = df! {
-            "Quarter" => vec!["2024 M01"; 8],
-            "State" => [vec!["A"; 4], vec!["B"; 4]].concat(),
-            "Region" => [vec!["AA"; 2], vec!["AB"; 2], vec!["BA"; 2], vec!["BB"; 2]].concat(),
-            "Purpose" => ["X", "Y", "X", "Y", "X", "Y", "X", "Y"],
-            "Value" => [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+            "Quarter" => ["1998 Q1", "1998 Q1", "1998 Q1", "1998 Q2"],
+            "State" => ["A", "A", "B", "A"],
+            "Region" => ["AA", "AB", "BA", "AA"],
+            "Value" => [Some(1.0), None, Some(3.0), Some(4.0)],
         }
         .unwrap();
 
-        let spec = HierarchySpec::new(
-            vec!["State".into(), "Region".into()],
-            vec!["Purpose".into()],
-        );
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
         let hts = HierarchicalTimeSeries::new(df, spec, "Quarter", "Value").unwrap();
 
-        let summary = hts.summary();
-        println!("{}", summary);
+        let with_counts = hts.aggregate_all_with_counts().unwrap();
+        let plain = hts.aggregate_all().unwrap();
+        assert_eq!(with_counts.height(), plain.height());
+        assert!(
+            with_counts
+                .column("Value")
+                .unwrap()
+                .equals_missing(plain.column("Value").unwrap())
+        );
 
-        assert!(summary.n_series >= 1);
-        assert!(summary.n_bottom >= 1);
+        let total_q1 = with_counts
+            .clone()
+            .lazy()
+            .filter(
+                col("State")
+                    .eq(lit(AGGREGATED_LABEL))
+                    .and(col("Quarter").eq(lit("1998 Q1"))),
+            )
+            .collect()
+            .unwrap();
+        assert_eq!(total_q1.height(), 1);
+        // The null observation in A/AB is not counted
+        assert_eq!(
+            total_q1.column("count").unwrap().get(0).unwrap(),
+            AnyValue::UInt32(2)
+        );
+    }
+
+    #[test]
+    fn test_aggregate_all_expr() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[2], 3, 4);
+        let value_col = crate::testing::VALUE_COL;
+
+        let sums = hts.aggregate_all_expr(col(value_col).sum()).unwrap();
+        assert!(sums.equals(&hts.aggregate_all().unwrap()));
+
+        let means = hts.aggregate_all_expr(col(value_col).mean()).unwrap();
+        assert_eq!(means.schema(), sums.schema());
+
+        // Each value is the sum divided by the 8 bottom series per period
+        let total = |df: &DataFrame| df.column(value_col).unwrap().f64().unwrap().get(0).unwrap();
+        assert!((total(&means) - total(&sums) / 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregate_streaming_matches_aggregate_all() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[2], 3, 1);
+        let (time_col, value_col) = (crate::testing::TIME_COL, crate::testing::VALUE_COL);
+        let mut sort_cols: Vec<&str> = hts.spec().all_columns();
+        sort_cols.push(time_col);
+
+        let expected = hts
+            .aggregate_all()
+            .unwrap()
+            .sort(sort_cols.clone(), Default::default())
+            .unwrap();
+        let scan = hts.bottom_data().clone().lazy();
+        let streamed = HierarchicalTimeSeries::aggregate_streaming(
+            scan.clone(),
+            hts.spec(),
+            time_col,
+            value_col,
+        )
+        .unwrap()
+        .collect()
+        .unwrap()
+        .sort(sort_cols.clone(), Default::default())
+        .unwrap();
+        assert!(expected.equals(&streamed));
+
+        let sequential = HtsConfig {
+            parallel: false,
+            ..Default::default()
+        };
+        let streamed = HierarchicalTimeSeries::aggregate_streaming_with_config(
+            scan.clone(),
+            hts.spec(),
+            time_col,
+            value_col,
+            &sequential,
+        )
+        .unwrap()
+        .collect()
+        .unwrap()
+        .sort(sort_cols, Default::default())
+        .unwrap();
+        assert!(expected.equals(&streamed));
+
+        let tree = HierarchyTree::from_lazy(scan.clone(), hts.spec()).unwrap();
+        assert_eq!(tree.n_series(), hts.n_series());
+        assert_eq!(tree.n_bottom(), hts.n_bottom());
+
+        assert!(
+            HierarchicalTimeSeries::aggregate_streaming(scan, hts.spec(), "Missing", value_col)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_aggregate_all_matches_serial_levels() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[2], 4, 3);
+        let (time_col, value_col) = (crate::testing::TIME_COL, crate::testing::VALUE_COL);
+        let parallel = hts.aggregate_all().unwrap();
+
+        // Collect each level on its own and stack them in level order
+        let all_cols = hts.spec().all_columns();
+        let mut serial: Option<DataFrame> = None;
+        for level_cols in hts.spec().level_combinations() {
+            let mut keys: Vec<Expr> = level_cols.iter().map(|c| col(c.as_str())).collect();
+            keys.push(col(time_col));
+            let mut lf = hts
+                .bottom_data()
+                .clone()
+                .lazy()
+                .group_by_stable(keys)
+                .agg([col(value_col).sum()]);
+            for &name in &all_cols {
+                if !level_cols.iter().any(|c| c == name) {
+                    lf = lf.with_column(lit("<aggregated>").alias(name));
+                }
+            }
+            let mut select: Vec<Expr> = all_cols.iter().map(|c| col(*c)).collect();
+            select.extend([col(time_col), col(value_col)]);
+            let level = lf.select(select).collect().unwrap();
+
+            serial = Some(match serial {
+                Some(mut acc) => {
+                    acc.vstack_mut(&level).unwrap();
+                    acc
+                }
+                None => level,
+            });
+        }
+
+        assert!(parallel.equals(&serial.unwrap()));
+        assert!(parallel.equals(&hts.aggregate_all().unwrap()));
+    }
+
+    #[test]
+    fn test_n_threads() {
+        let hts = crate::testing::random_hierarchy(&[2, 3], &[2], 4, 3);
+        let (time_col, value_col) = (crate::testing::TIME_COL, crate::testing::VALUE_COL);
+        let expected = hts.aggregate_all().unwrap();
+        let reconciled = hts.reconciled_frame(&expected, ReconMethod::Ols).unwrap();
+
+        for n_threads in [1, 2] {
+            let config = HtsConfig {
+                n_threads: Some(n_threads),
+                ..Default::default()
+            };
+            let pooled = HierarchicalTimeSeries::with_config(
+                hts.bottom_data().clone(),
+                hts.spec().clone(),
+                time_col,
+                value_col,
+                config,
+            )
+            .unwrap();
+            assert_eq!(pooled.install(rayon::current_num_threads), n_threads);
+            // Derived datasets share the pool
+            let subtree = pooled.subtree("Total").unwrap();
+            assert_eq!(subtree.install(rayon::current_num_threads), n_threads);
+
+            assert!(pooled.aggregate_all().unwrap().equals(&expected));
+            let pooled_reconciled = pooled
+                .reconciled_frame(&expected, ReconMethod::Ols)
+                .unwrap();
+            let values = |df: &DataFrame| -> Vec<f64> {
+                let column = df.column(value_col).unwrap().f64().unwrap().clone();
+                column.into_no_null_iter().collect()
+            };
+            for (a, b) in values(&pooled_reconciled).iter().zip(values(&reconciled)) {
+                assert!((a - b).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_csv_glob() {
+        let dir = std::env::temp_dir().join(format!("hts_csv_glob_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("trips_1998.csv"),
+            "Quarter,State,Value\n1998 Q1,A,1\n1998 Q1,B,2\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("trips_1999.csv"),
+            "Quarter,State,Value\n1999 Q1,A,1.5\n1999 Q1,B,2.5\n",
+        )
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into()]);
+        let pattern = dir.join("trips_*.csv");
+        let hts = HierarchicalTimeSeries::from_csv_glob(
+            pattern.to_str().unwrap(),
+            spec.clone(),
+            "Quarter",
+            "Value",
+        )
+        .unwrap();
+        assert_eq!(hts.bottom_data().height(), 4);
+        assert_eq!(hts.n_periods(), 2);
+
+        // A file with different columns is rejected
+        std::fs::write(
+            dir.join("trips_2000.csv"),
+            "Quarter,State,Trips\n2000 Q1,A,1\n",
+        )
+        .unwrap();
+        let err = HierarchicalTimeSeries::from_csv_glob(
+            pattern.to_str().unwrap(),
+            spec.clone(),
+            "Quarter",
+            "Value",
+        )
+        .unwrap_err();
+        assert!(matches!(err, HtsError::SchemaMismatch(_)), "{err}");
+
+        // ...unless its value column is among the candidates
+        let hts = HierarchicalTimeSeries::from_csv_glob_with_value_candidates(
+            pattern.to_str().unwrap(),
+            spec.clone(),
+            "Quarter",
+            "Value",
+            &["Trips"],
+        )
+        .unwrap();
+        assert_eq!(hts.bottom_data().height(), 5);
+        assert_eq!(hts.n_periods(), 3);
+
+        let err = HierarchicalTimeSeries::from_csv_glob_with_value_candidates(
+            pattern.to_str().unwrap(),
+            spec.clone(),
+            "Quarter",
+            "Value",
+            &["Visitors"],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("trips_2000.csv"), "{err}");
+
+        let none = dir.join("missing_*.csv");
+        assert!(
+            HierarchicalTimeSeries::from_csv_glob(none.to_str().unwrap(), spec, "Quarter", "Value")
+                .is_err()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,254 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Utilities for checking results against other tools.
+//!
+//! Users migrating from R (`fable`, `hts`) typically want to confirm that
+//! this crate reproduces their existing aggregates before switching.
+//! [`compare_aggregations`] lines up two long-format frames on their key
+//! columns and reports every row that disagrees.
+
+use crate::error::{HtsError, Result};
+use crate::tolerance::Tolerance;
+use polars::prelude::*;
+
+/// Temporary names of the compared value columns, unlikely to clash with keys.
+const RUST_VALUE: &str = "__rust_value";
+const R_VALUE: &str = "__r_value";
+
+/// Outcome of [`compare_aggregations`].
+#[derive(Debug, Clone, Default)]
+pub struct CompareReport {
+    /// Number of key combinations present in both frames.
+    pub n_matched: usize,
+    /// Matched rows whose values disagree beyond the tolerance, ordered by
+    /// key.
+    pub mismatches: Vec<RowDiff>,
+    /// Keys, in key column order, found only in the Rust frame.
+    pub only_in_rust: Vec<Vec<String>>,
+    /// Keys, in key column order, found only in the R frame.
+    pub only_in_r: Vec<Vec<String>>,
+    /// Sum of the non-null values of the Rust frame.
+    pub rust_total: f64,
+    /// Sum of the non-null values of the R frame.
+    pub r_total: f64,
+}
+
+impl CompareReport {
+    /// Returns true if both frames have the same keys and agree on every
+    /// value.
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty() && self.only_in_rust.is_empty() && self.only_in_r.is_empty()
+    }
+}
+
+/// A single disagreement in a [`CompareReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowDiff {
+    /// Key values, in key column order.
+    pub keys: Vec<String>,
+    /// Value in the Rust frame, or `None` if it was null.
+    pub rust: Option<f64>,
+    /// Value in the R frame, or `None` if it was null.
+    pub r: Option<f64>,
+}
+
+/// Compares two aggregated frames row by row.
+///
+/// Rows are matched on the `keys` columns, compared as strings so that, for
+/// instance, a period read as text on one side and produced by this crate on
+/// the other still line up. Matched values are compared within `tol`; a null
+/// value agrees only with another null. Neither frame needs to be sorted.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::Tolerance;
+/// use hts_core::interop::compare_aggregations;
+/// use hts_core::polars::prelude::*;
+///
+/// let rust = df! { "State" => ["A", "B"], "Trips" => [1.0, 2.0] }.unwrap();
+/// let r = df! { "State" => ["B", "A"], "Trips" => [2.0, 1.0 + 1e-12] }.unwrap();
+///
+/// let report = compare_aggregations(&rust, &r, &["State"], "Trips", Tolerance::default()).unwrap();
+/// assert!(report.is_clean());
+/// assert_eq!(report.n_matched, 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`HtsError::ColumnNotFound`] if either frame lacks a key column or
+/// the value column, and [`HtsError::SchemaMismatch`] if the keys do not
+/// identify the rows of a frame uniquely.
+pub fn compare_aggregations(
+    rust: &DataFrame,
+    r: &DataFrame,
+    keys: &[&str],
+    value: &str,
+    tol: Tolerance,
+) -> Result<CompareReport> {
+    let rust_rows = prepare(rust, "Rust", keys, value, RUST_VALUE)?;
+    let r_rows = prepare(r, "R", keys, value, R_VALUE)?;
+
+    let sum = |df: &DataFrame, name: &str| -> Result<f64> {
+        Ok(df.column(name)?.f64()?.sum().unwrap_or_default())
+    };
+    let mut report = CompareReport {
+        rust_total: sum(&rust_rows, RUST_VALUE)?,
+        r_total: sum(&r_rows, R_VALUE)?,
+        ..Default::default()
+    };
+
+    let on: Vec<Expr> = keys.iter().map(|&k| col(k)).collect();
+    let joined = rust_rows
+        .lazy()
+        .with_column(lit(true).alias("__in_rust"))
+        .join(
+            r_rows.lazy().with_column(lit(true).alias("__in_r")),
+            on.clone(),
+            on,
+            JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+        )
+        .sort(keys.to_vec(), SortMultipleOptions::default())
+        .collect()?;
+
+    let key_values = keys
+        .iter()
+        .map(|&k| Ok(joined.column(k)?.str()?.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    let in_rust = joined.column("__in_rust")?.bool()?;
+    let in_r = joined.column("__in_r")?.bool()?;
+    let rust_values = joined.column(RUST_VALUE)?.f64()?;
+    let r_values = joined.column(R_VALUE)?.f64()?;
+
+    for row in 0..joined.height() {
+        let row_keys = || -> Vec<String> {
+            key_values
+                .iter()
+                .map(|values| values.get(row).unwrap_or_default().to_string())
+                .collect()
+        };
+        match (in_rust.get(row).is_some(), in_r.get(row).is_some()) {
+            (true, true) => {
+                report.n_matched += 1;
+                let (a, b) = (rust_values.get(row), r_values.get(row));
+                let agree = match (a, b) {
+                    (Some(a), Some(b)) => tol.approx_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                };
+                if !agree {
+                    report.mismatches.push(RowDiff {
+                        keys: row_keys(),
+                        rust: a,
+                        r: b,
+                    });
+                }
+            }
+            (true, false) => report.only_in_rust.push(row_keys()),
+            _ => report.only_in_r.push(row_keys()),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Selects the keys (as strings) and the value (as `f64`, renamed to
+/// `alias`) of one side, checking that the keys are unique.
+fn prepare(
+    df: &DataFrame,
+    side: &str,
+    keys: &[&str],
+    value: &str,
+    alias: &str,
+) -> Result<DataFrame> {
+    for &name in keys.iter().chain([&value]) {
+        if df.column(name).is_err() {
+            return Err(HtsError::ColumnNotFound(format!(
+                "{name} (in the {side} frame)"
+            )));
+        }
+    }
+
+    let mut exprs: Vec<Expr> = keys
+        .iter()
+        .map(|&k| col(k).cast(DataType::String))
+        .collect();
+    exprs.push(col(value).cast(DataType::Float64).alias(alias));
+    let rows = df.clone().lazy().select(exprs).collect()?;
+
+    if rows.select(keys.iter().copied())?.is_duplicated()?.any() {
+        return Err(HtsError::SchemaMismatch(format!(
+            "Keys {keys:?} do not identify the rows of the {side} frame uniquely"
+        )));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_aggregations() {
+        let rust = df! {
+            "State" => ["A", "A", "B", "C"],
+            "Year" => [2000i64, 2001, 2000, 2000],
+            "Trips" => [Some(1.0), Some(2.0), Some(3.0), None],
+        }
+        .unwrap();
+        let r = df! {
+            "State" => ["B", "A", "A", "D"],
+            "Year" => ["2000", "2001", "2000", "2000"],
+            "Trips" => [3.0, 2.5, 1.0, 4.0],
+        }
+        .unwrap();
+        let tol = Tolerance::default();
+
+        let report = compare_aggregations(&rust, &r, &["State", "Year"], "Trips", tol).unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.n_matched, 3);
+        assert_eq!(
+            report.mismatches,
+            vec![RowDiff {
+                keys: vec!["A".into(), "2001".into()],
+                rust: Some(2.0),
+                r: Some(2.5),
+            }]
+        );
+        assert_eq!(
+            report.only_in_rust,
+            vec![vec!["C".to_string(), "2000".into()]]
+        );
+        assert_eq!(report.only_in_r, vec![vec!["D".to_string(), "2000".into()]]);
+        assert_eq!((report.rust_total, report.r_total), (6.0, 10.5));
+
+        assert!(
+            compare_aggregations(&rust, &rust, &["State", "Year"], "Trips", tol)
+                .unwrap()
+                .is_clean()
+        );
+        assert!(matches!(
+            compare_aggregations(&rust, &r, &["State"], "Trips", tol),
+            Err(HtsError::SchemaMismatch(_))
+        ));
+        assert!(matches!(
+            compare_aggregations(&rust, &r, &["State", "Year"], "Value", tol),
+            Err(HtsError::ColumnNotFound(_))
+        ));
+    }
+}
@@ -137,6 +137,29 @@ println!("S matrix:\n{:?}", s);
 // #         "São Paulo/São Paulo/Agriculture",
 // #         "São Paulo/São Paulo/Industry",
 // #     ],
+// #     row_level_labels: [
+// #         "Total",
+// #         "Sector",
+// #         "Sector",
+// #         "State",
+// #         "State",
+// #         "State/Sector",
+// #         "State/Sector",
+// #         "State/Sector",
+// #         "State/Sector",
+// #         "State/City",
+// #         "State/City",
+// #         "State/City",
+// #         "State/City",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #         "State/City/Sector",
+// #     ],
 // # }
 
 // Print summary
@@ -164,25 +187,25 @@ println!("Aggregated Data:\n{}", aggregated);
 // # ╞════════════════╪═════════════════╪══════════════╪═════════╪══════╡
 // # │ <aggregated>   ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q1 ┆ 5070 │
 // # │ <aggregated>   ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q2 ┆ 6450 │
-// # │ <aggregated>   ┆ <aggregated>    ┆ Agriculture  ┆ 2024 Q2 ┆ 2150 │
+// # │ <aggregated>   ┆ <aggregated>    ┆ Industry     ┆ 2024 Q1 ┆ 3450 │
 // # │ <aggregated>   ┆ <aggregated>    ┆ Agriculture  ┆ 2024 Q1 ┆ 1620 │
 // # │ <aggregated>   ┆ <aggregated>    ┆ Industry     ┆ 2024 Q2 ┆ 4300 │
-// # │ <aggregated>   ┆ <aggregated>    ┆ Industry     ┆ 2024 Q1 ┆ 3450 │
+// # │ <aggregated>   ┆ <aggregated>    ┆ Agriculture  ┆ 2024 Q2 ┆ 2150 │
 // # │ Rio de Janeiro ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q1 ┆ 1770 │
-// # │ São Paulo      ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q2 ┆ 3800 │
-// # │ Rio de Janeiro ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q2 ┆ 2650 │
 // # │ São Paulo      ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q1 ┆ 3300 │
+// # │ Rio de Janeiro ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q2 ┆ 2650 │
+// # │ São Paulo      ┆ <aggregated>    ┆ <aggregated> ┆ 2024 Q2 ┆ 3800 │
 // # │ …              ┆ …               ┆ …            ┆ …       ┆ …    │
-// # │ Rio de Janeiro ┆ Duque de Caxias ┆ Agriculture  ┆ 2024 Q2 ┆ 150  │
 // # │ São Paulo      ┆ Campinas        ┆ Industry     ┆ 2024 Q1 ┆ 300  │
-// # │ Rio de Janeiro ┆ Duque de Caxias ┆ Agriculture  ┆ 2024 Q1 ┆ 120  │
-// # │ Rio de Janeiro ┆ Rio de Janeiro  ┆ Industry     ┆ 2024 Q1 ┆ 1000 │
 // # │ São Paulo      ┆ Campinas        ┆ Agriculture  ┆ 2024 Q1 ┆ 200  │
-// # │ São Paulo      ┆ São Paulo       ┆ Industry     ┆ 2024 Q1 ┆ 2000 │
+// # │ Rio de Janeiro ┆ Rio de Janeiro  ┆ Industry     ┆ 2024 Q2 ┆ 1500 │
+// # │ Rio de Janeiro ┆ Rio de Janeiro  ┆ Agriculture  ┆ 2024 Q2 ┆ 800  │
+// # │ Rio de Janeiro ┆ Duque de Caxias ┆ Industry     ┆ 2024 Q2 ┆ 200  │
+// # │ Rio de Janeiro ┆ Duque de Caxias ┆ Agriculture  ┆ 2024 Q2 ┆ 150  │
 // # │ São Paulo      ┆ São Paulo       ┆ Industry     ┆ 2024 Q2 ┆ 2200 │
-// # │ Rio de Janeiro ┆ Rio de Janeiro  ┆ Agriculture  ┆ 2024 Q1 ┆ 500  │
 // # │ São Paulo      ┆ São Paulo       ┆ Agriculture  ┆ 2024 Q2 ┆ 900  │
 // # │ São Paulo      ┆ Campinas        ┆ Industry     ┆ 2024 Q2 ┆ 400  │
+// # │ São Paulo      ┆ Campinas        ┆ Agriculture  ┆ 2024 Q2 ┆ 300  │
 // # └────────────────┴─────────────────┴──────────────┴─────────┴──────┘
 ```
 
@@ -195,17 +218,61 @@ The parser automatically detects standard string formats:
 - **Monthly**: `"2024 M01"`
 - **Weekly**: `"2024 W01"`
 - **Daily**: `"2024-01-01"`
+
+Plain integer indices with no calendar meaning (`"1"`, `"2"`, ...) are read as
+`Period::Ordinal` when `HtsConfig::period_format` is `PeriodFormat::Ordinal`.
+
+# Parallelism
+
+Three things may run on multiple threads:
+
+- **Polars queries** (tree construction, aggregation, period parsing) run on
+  Polars' global thread pool. It is sized once per process, from the
+  `POLARS_MAX_THREADS` environment variable, before its first use.
+- **Per-level aggregation**: `aggregate_all()` and friends evaluate the
+  group-by of every level concurrently. Set `HtsConfig::parallel` to `false`
+  (or pass such a config to `aggregate_streaming_with_config()`) to evaluate
+  them one after another.
+- **Linear algebra** (reconciliation, rank) uses `faer`, which runs on the
+  rayon thread pool it is called from.
+
+`HtsConfig::n_threads` gives a dataset its own pool of that many threads:
+aggregation queries are collected and reconciliation is solved inside it, so
+`faer` uses at most that many threads, and `Some(1)` also evaluates the levels
+one at a time. Polars dispatches its kernels to its global pool from any
+thread, and Polars 0.46 cannot bound that pool per call, so a fully
+single-threaded run (e.g., for benchmarking) still needs
+`POLARS_MAX_THREADS=1` alongside `n_threads: Some(1)`.
 */
 
+pub mod config;
+pub mod cross_temporal;
 pub mod error;
 pub mod hierarchy;
 pub mod hts;
+pub mod interop;
 pub mod period;
+pub mod reconciliation;
 pub mod summation_matrix;
+pub mod testing;
+pub mod tolerance;
 
+pub use config::{
+    AggregationFn, Collation, CsvImportOptions, HtsConfig, NaPeriodPolicy, PeriodFormat, ValueKind,
+};
+pub use cross_temporal::CrossTemporalHts;
 pub use error::{HtsError, Result};
-pub use hierarchy::{HierarchySpec, HierarchyTree, Node};
-pub use hts::{HierarchicalTimeSeries, HtsSummary};
-pub use period::Period;
+pub use hierarchy::{
+    BalanceReport, DEFAULT_TOTAL_LABEL, HierarchySpec, HierarchyTree, Node, StructureKind,
+};
+pub use hts::{
+    AGGREGATED_LABEL, BuildDiagnostics, DiffReport, HierarchicalTimeSeries, HtsSummary, ValueDiff,
+};
+pub use period::{Frequency, Period};
 pub use polars;
+pub use reconciliation::{
+    IterOpts, IterResult, LevelAccuracy, ReconContext, ReconMethod, ReconReport, ReconResult,
+    ReconciliationMatrix, TopDownMethod,
+};
 pub use summation_matrix::SummationMatrix;
+pub use tolerance::Tolerance;
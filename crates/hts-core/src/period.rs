@@ -17,15 +17,17 @@
 
 //! Time period types for time series indexing.
 
+use crate::config::PeriodFormat;
 use crate::error::{HtsError, Result};
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use std::fmt;
 use std::str::FromStr;
 
 /// A time period used as the index for time series data.
 ///
 /// Supports various frequencies including Annual, Quarterly, Monthly,
-/// Weekly, and Daily.
+/// Weekly, and Daily, as well as plain ordinal indices with no calendar
+/// meaning.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Period {
     /// Annual period (e.g., "1998").
@@ -35,12 +37,71 @@ pub enum Period {
     /// Monthly period (e.g., "1998 M01").
     Monthly(i32, u8),
     /// Weekly period (e.g., "1998 W01").
+    ///
+    /// The year is the ISO week-numbering year, not the calendar year of the
+    /// week's days: 2021-01-01 falls in "2020 W53". Because of that, ordering
+    /// by (year, week) matches the order of the weeks' start dates, including
+    /// around year boundaries.
     Weekly(i32, u8),
     /// Daily period (e.g., "1998-01-01").
     Daily(NaiveDate),
+    /// Ordinal time index with no calendar meaning (e.g., "17").
+    ///
+    /// Bare integers parse as [`Period::Annual`] by default; use
+    /// [`Period::parse_ordinal`] or [`PeriodFormat::Ordinal`] to read them as
+    /// ordinal indices instead.
+    Ordinal(i64),
+}
+
+/// Calendar frequency of a [`Period`], used to bucket dates with
+/// [`Period::from_date`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frequency {
+    /// Calendar years.
+    Annual,
+    /// Calendar quarters.
+    Quarterly,
+    /// Calendar months.
+    Monthly,
+    /// ISO weeks, starting on Monday.
+    Weekly,
+    /// Single days.
+    Daily,
 }
 
 impl Period {
+    /// Returns the period of frequency `freq` containing `date`.
+    ///
+    /// Weekly periods follow the ISO calendar, so a date early in January
+    /// may fall in the last week of the previous ISO year. For every
+    /// frequency, [`Self::to_naive_date`] of the result is the first day of
+    /// the bucket.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use hts_core::{Frequency, Period};
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+    /// assert_eq!(Period::from_date(date, Frequency::Quarterly), Period::Quarterly(2024, 2));
+    ///
+    /// let new_year = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+    /// assert_eq!(Period::from_date(new_year, Frequency::Weekly), Period::Weekly(2020, 53));
+    /// ```
+    pub fn from_date(date: NaiveDate, freq: Frequency) -> Self {
+        match freq {
+            Frequency::Annual => Self::Annual(date.year()),
+            Frequency::Quarterly => Self::Quarterly(date.year(), (date.month0() / 3 + 1) as u8),
+            Frequency::Monthly => Self::Monthly(date.year(), date.month() as u8),
+            Frequency::Weekly => {
+                let week = date.iso_week();
+                Self::Weekly(week.year(), week.week() as u8)
+            }
+            Frequency::Daily => Self::Daily(date),
+        }
+    }
+
     /// Parses a string into a `Period`.
     ///
     /// Auto-detects the format:
@@ -49,6 +110,10 @@ impl Period {
     /// - "YYYY MN" -> Monthly
     /// - "YYYY WN" -> Weekly
     /// - "YYYY-MM-DD" -> Daily
+    ///
+    /// Week 53 is accepted only in ISO years that have one (e.g. 2020 but
+    /// not 2021).
+    #[allow(clippy::collapsible_if)]
     pub fn parse(s: &str) -> Result<Self> {
         let s = s.trim();
 
@@ -112,6 +177,11 @@ impl Period {
                         "Week must be 1-53, got {value}"
                     )));
                 }
+                if NaiveDate::from_isoywd_opt(year, value, chrono::Weekday::Mon).is_none() {
+                    return Err(HtsError::InvalidPeriod(format!(
+                        "ISO year {year} has no week {value}"
+                    )));
+                }
                 Ok(Self::Weekly(year, value as u8))
             }
             _ => Err(HtsError::InvalidPeriod(format!(
@@ -120,9 +190,111 @@ impl Period {
         }
     }
 
-    /// Returns the start date of the period.
-    pub fn to_naive_date(self) -> NaiveDate {
+    /// Parses a bare integer into a [`Period::Ordinal`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::Period;
+    ///
+    /// assert_eq!(Period::parse_ordinal(" 42 ").unwrap(), Period::Ordinal(42));
+    /// assert!(Period::parse_ordinal("1998 Q1").is_err());
+    /// ```
+    pub fn parse_ordinal(s: &str) -> Result<Self> {
+        let s = s.trim();
+        s.parse()
+            .map(Self::Ordinal)
+            .map_err(|_| HtsError::InvalidPeriod(format!("Invalid ordinal index: '{s}'")))
+    }
+
+    /// Parses a string according to `format`.
+    pub fn parse_as(s: &str, format: PeriodFormat) -> Result<Self> {
+        match format {
+            PeriodFormat::Calendar => Self::parse(s),
+            PeriodFormat::Ordinal => Self::parse_ordinal(s),
+        }
+    }
+
+    /// Returns the stable string form of this period.
+    ///
+    /// This is exactly the [`Display`](fmt::Display) output ("1998",
+    /// "1998 Q1", "1998 M01", "1998 W01", "1998-01-01"), and the form used
+    /// when serializing with the `serde` feature. It is a compatibility
+    /// contract: clients can parse it back with [`Period::parse`].
+    ///
+    /// Ordinal periods are written as their bare index, which
+    /// [`Period::parse`] reads as an annual period; use
+    /// [`Period::parse_ordinal`] to read them back.
+    pub fn to_iso_like(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses the string form produced by [`Self::to_iso_like`].
+    ///
+    /// Equivalent to [`Period::parse`].
+    pub fn from_iso_like(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+
+    /// Returns the quarter enclosing this period.
+    ///
+    /// Monthly and daily periods map to the quarter containing them, and a
+    /// quarterly period maps to itself. Returns `None` for annual periods,
+    /// which are coarser than a quarter, and for weekly periods, which may
+    /// straddle two quarters, and for ordinal periods.
+    pub fn to_quarter(self) -> Option<Self> {
         match self {
+            Self::Annual(_) | Self::Weekly(..) | Self::Ordinal(_) => None,
+            Self::Quarterly(..) => Some(self),
+            Self::Monthly(y, m) => Some(Self::Quarterly(y, (m - 1) / 3 + 1)),
+            Self::Daily(d) => Some(Self::Quarterly(d.year(), (d.month0() / 3 + 1) as u8)),
+        }
+    }
+
+    /// Returns the year of this period.
+    ///
+    /// For weekly periods this is the ISO week-numbering year, the year in
+    /// their label. Returns `None` for ordinal periods.
+    pub fn year(self) -> Option<i32> {
+        match self {
+            Self::Annual(y) | Self::Quarterly(y, _) | Self::Monthly(y, _) | Self::Weekly(y, _) => {
+                Some(y)
+            }
+            Self::Daily(d) => Some(d.year()),
+            Self::Ordinal(_) => None,
+        }
+    }
+
+    /// Returns the year enclosing this period.
+    ///
+    /// Weekly periods map to their ISO week-numbering year, which is the year
+    /// in their label. An annual period maps to itself. Returns `None` for
+    /// ordinal periods, which have no year.
+    pub fn to_annual(self) -> Option<Self> {
+        self.year().map(Self::Annual)
+    }
+
+    /// Returns the position of the period within its year: the quarter
+    /// (1-4), month (1-12) or ISO week (1-53).
+    ///
+    /// Returns `None` for annual and ordinal periods, which have no season,
+    /// and for daily periods, where day-of-year and day-of-week would both be
+    /// plausible readings.
+    pub fn season_index(self) -> Option<u32> {
+        match self {
+            Self::Quarterly(_, s) | Self::Monthly(_, s) | Self::Weekly(_, s) => Some(s as u32),
+            Self::Annual(_) | Self::Daily(_) | Self::Ordinal(_) => None,
+        }
+    }
+
+    /// Returns the start date of the period, or `None` for ordinal periods,
+    /// which have no calendar meaning.
+    ///
+    /// Weekly periods start on the Monday of their ISO week. A week built
+    /// directly that does not exist in its ISO year, such as
+    /// `Weekly(2021, 53)`, also returns `None`.
+    pub fn to_naive_date(self) -> Option<NaiveDate> {
+        let date = match self {
             Self::Annual(y) => NaiveDate::from_ymd_opt(y, 1, 1).expect("Valid annual date"),
             Self::Quarterly(y, q) => {
                 let month = match q {
@@ -138,12 +310,12 @@ impl Period {
                 NaiveDate::from_ymd_opt(y, m as u32, 1).expect("Valid monthly date")
             }
             Self::Weekly(y, w) => {
-                // ISO week date approximation (first day of week)
-                NaiveDate::from_isoywd_opt(y, w as u32, chrono::Weekday::Mon)
-                    .unwrap_or_else(|| NaiveDate::from_ymd_opt(y, 1, 1).unwrap())
+                return NaiveDate::from_isoywd_opt(y, w as u32, chrono::Weekday::Mon);
             }
             Self::Daily(d) => d,
-        }
+            Self::Ordinal(_) => return None,
+        };
+        Some(date)
     }
 }
 
@@ -155,10 +327,31 @@ impl fmt::Display for Period {
             Self::Monthly(y, m) => write!(f, "{} M{:02}", y, m),
             Self::Weekly(y, w) => write!(f, "{} W{:02}", y, w),
             Self::Daily(d) => write!(f, "{}", d.format("%Y-%m-%d")),
+            Self::Ordinal(i) => write!(f, "{}", i),
         }
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Period {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_iso_like())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Period {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Self::from_iso_like(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 impl FromStr for Period {
     type Err = HtsError;
 
@@ -221,10 +414,227 @@ mod tests {
         assert!(m1 < m2);
     }
 
+    #[test]
+    fn test_weekly_ordering_across_years() {
+        // 2020 has an ISO week 53, which ends on 2021-01-03
+        let weeks = [
+            Period::Weekly(2019, 52),
+            Period::Weekly(2020, 1),
+            Period::Weekly(2020, 52),
+            Period::Weekly(2020, 53),
+            Period::Weekly(2021, 1),
+            Period::Weekly(2021, 52),
+            Period::Weekly(2022, 1),
+        ];
+
+        for pair in weeks.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].to_naive_date() < pair[1].to_naive_date());
+        }
+
+        // The first ISO week of 2020 starts in December 2019
+        assert_eq!(
+            Period::Weekly(2020, 1).to_naive_date(),
+            NaiveDate::from_ymd_opt(2019, 12, 30)
+        );
+        assert_eq!(
+            Period::Weekly(2020, 53).to_naive_date(),
+            NaiveDate::from_ymd_opt(2020, 12, 28)
+        );
+    }
+
+    #[test]
+    fn test_from_date() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let cases = [
+            (Frequency::Annual, Period::Annual(2024)),
+            (Frequency::Quarterly, Period::Quarterly(2024, 2)),
+            (Frequency::Monthly, Period::Monthly(2024, 5)),
+            (Frequency::Weekly, Period::Weekly(2024, 20)),
+            (Frequency::Daily, Period::Daily(date(2024, 5, 15))),
+        ];
+        for (freq, expected) in cases {
+            let period = Period::from_date(date(2024, 5, 15), freq);
+            assert_eq!(period, expected);
+            assert!(period.to_naive_date().unwrap() <= date(2024, 5, 15));
+        }
+
+        // ISO weeks around year boundaries
+        assert_eq!(
+            Period::from_date(date(2019, 12, 30), Frequency::Weekly),
+            Period::Weekly(2020, 1)
+        );
+        assert_eq!(
+            Period::from_date(date(2021, 1, 3), Frequency::Weekly),
+            Period::Weekly(2020, 53)
+        );
+        assert_eq!(
+            Period::from_date(date(2021, 1, 4), Frequency::Weekly),
+            Period::Weekly(2021, 1)
+        );
+        assert_eq!(
+            Period::from_date(date(2024, 12, 31), Frequency::Quarterly),
+            Period::Quarterly(2024, 4)
+        );
+
+        // Inverse of to_naive_date on the first day of each bucket
+        for period in [
+            Period::Annual(1998),
+            Period::Quarterly(1998, 3),
+            Period::Monthly(1998, 12),
+            Period::Weekly(2020, 53),
+        ] {
+            let freq = match period {
+                Period::Annual(_) => Frequency::Annual,
+                Period::Quarterly(..) => Frequency::Quarterly,
+                Period::Monthly(..) => Frequency::Monthly,
+                _ => Frequency::Weekly,
+            };
+            assert_eq!(
+                Period::from_date(period.to_naive_date().unwrap(), freq),
+                period
+            );
+        }
+    }
+
+    #[test]
+    fn test_week_53() {
+        assert_eq!(Period::parse("2020 W53").unwrap(), Period::Weekly(2020, 53));
+        assert!(matches!(
+            Period::parse("2021 W53"),
+            Err(HtsError::InvalidPeriod(_))
+        ));
+        assert!(Period::parse("2021 W52").is_ok());
+        assert!(Period::parse("2021 W54").is_err());
+
+        assert_eq!(Period::Weekly(2021, 53).to_naive_date(), None);
+        assert_eq!(
+            Period::Weekly(2021, 52).to_naive_date(),
+            NaiveDate::from_ymd_opt(2021, 12, 27)
+        );
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(Period::Quarterly(1998, 1).to_string(), "1998 Q1");
         assert_eq!(Period::Monthly(1998, 1).to_string(), "1998 M01");
         assert_eq!(Period::Annual(1998).to_string(), "1998");
     }
+
+    #[test]
+    fn test_iso_like_format_is_pinned() {
+        let cases = [
+            (Period::Annual(1998), "1998"),
+            (Period::Quarterly(1998, 1), "1998 Q1"),
+            (Period::Monthly(1998, 1), "1998 M01"),
+            (Period::Monthly(2024, 12), "2024 M12"),
+            (Period::Weekly(1998, 1), "1998 W01"),
+            (
+                Period::Daily(NaiveDate::from_ymd_opt(1998, 1, 1).unwrap()),
+                "1998-01-01",
+            ),
+        ];
+
+        for (period, expected) in cases {
+            assert_eq!(period.to_iso_like(), expected);
+            assert_eq!(period.to_iso_like(), period.to_string());
+            assert_eq!(Period::from_iso_like(expected).unwrap(), period);
+            assert_eq!(Period::parse(&period.to_iso_like()).unwrap(), period);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        let periods = vec![Period::Quarterly(1998, 1), Period::Monthly(2024, 12)];
+        let json = serde_json::to_string(&periods).unwrap();
+        assert_eq!(json, r#"["1998 Q1","2024 M12"]"#);
+
+        let back: Vec<Period> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, periods);
+        assert!(serde_json::from_str::<Period>(r#""1998 X1""#).is_err());
+    }
+
+    #[test]
+    fn test_to_quarter() {
+        assert_eq!(
+            Period::Monthly(1998, 1).to_quarter(),
+            Some(Period::Quarterly(1998, 1))
+        );
+        assert_eq!(
+            Period::Monthly(1998, 3).to_quarter(),
+            Some(Period::Quarterly(1998, 1))
+        );
+        assert_eq!(
+            Period::Monthly(1998, 4).to_quarter(),
+            Some(Period::Quarterly(1998, 2))
+        );
+        assert_eq!(
+            Period::Monthly(1998, 12).to_quarter(),
+            Some(Period::Quarterly(1998, 4))
+        );
+        assert_eq!(
+            Period::Daily(NaiveDate::from_ymd_opt(1998, 7, 1).unwrap()).to_quarter(),
+            Some(Period::Quarterly(1998, 3))
+        );
+        assert_eq!(
+            Period::Quarterly(1998, 2).to_quarter(),
+            Some(Period::Quarterly(1998, 2))
+        );
+        assert_eq!(Period::Annual(1998).to_quarter(), None);
+        assert_eq!(Period::Weekly(1998, 14).to_quarter(), None);
+    }
+
+    #[test]
+    fn test_to_annual() {
+        let annual = Some(Period::Annual(1998));
+        assert_eq!(Period::Annual(1998).to_annual(), annual);
+        assert_eq!(Period::Quarterly(1998, 4).to_annual(), annual);
+        assert_eq!(Period::Monthly(1998, 12).to_annual(), annual);
+        assert_eq!(Period::Weekly(1998, 1).to_annual(), annual);
+        assert_eq!(
+            Period::Daily(NaiveDate::from_ymd_opt(1998, 12, 31).unwrap()).to_annual(),
+            annual
+        );
+        assert_eq!(Period::Ordinal(1998).to_annual(), None);
+    }
+
+    #[test]
+    fn test_year_and_season_index() {
+        let day = Period::Daily(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap());
+        let cases = [
+            (Period::Annual(1998), Some(1998), None),
+            (Period::Quarterly(1998, 3), Some(1998), Some(3)),
+            (Period::Monthly(1998, 12), Some(1998), Some(12)),
+            (Period::Weekly(2020, 53), Some(2020), Some(53)),
+            (day, Some(2021), None),
+            (Period::Ordinal(7), None, None),
+        ];
+        for (period, year, season) in cases {
+            assert_eq!(period.year(), year, "{period}");
+            assert_eq!(period.season_index(), season, "{period}");
+        }
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(Period::parse_ordinal("3").unwrap(), Period::Ordinal(3));
+        assert_eq!(Period::parse_ordinal("-1").unwrap(), Period::Ordinal(-1));
+        assert!(Period::parse_ordinal("1998.25").is_err());
+
+        // Bare integers stay annual unless ordinal parsing is requested
+        assert_eq!(
+            Period::parse_as("1998", PeriodFormat::Calendar).unwrap(),
+            Period::Annual(1998)
+        );
+        assert_eq!(
+            Period::parse_as("1998", PeriodFormat::Ordinal).unwrap(),
+            Period::Ordinal(1998)
+        );
+
+        assert!(Period::Ordinal(2) < Period::Ordinal(10));
+        assert_eq!(Period::Ordinal(10).to_string(), "10");
+        assert_eq!(Period::Ordinal(10).to_naive_date(), None);
+        assert_eq!(Period::Ordinal(10).to_quarter(), None);
+    }
 }
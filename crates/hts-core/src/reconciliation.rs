@@ -0,0 +1,865 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Forecast reconciliation.
+//!
+//! Linear reconciliation maps incoherent base forecasts $\hat{\mathbf{y}}$ for
+//! all n series to coherent forecasts $\tilde{\mathbf{y}} = \mathbf{S}\mathbf{G}\hat{\mathbf{y}}$,
+//! where the m × n matrix G depends on the chosen method. For the least
+//! squares family, $\mathbf{G} = (\mathbf{S}'\mathbf{W}^{-1}\mathbf{S})^{-1}\mathbf{S}'\mathbf{W}^{-1}$.
+
+use crate::error::{HtsError, Result};
+use crate::summation_matrix::SummationMatrix;
+use faer::prelude::*;
+use faer::{Mat, Side};
+use std::collections::HashMap;
+
+/// Linear reconciliation method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconMethod {
+    /// Bottom-up: keeps the bottom-level base forecasts and aggregates them.
+    BottomUp,
+
+    /// Ordinary least squares (W = I).
+    Ols,
+
+    /// Weighted least squares with structural scaling, where each series is
+    /// weighted by the number of bottom-level series it aggregates
+    /// (W = diag(S1)).
+    WlsStructural,
+}
+
+/// How top-down reconciliation computes the proportions used to split the
+/// total among the bottom-level series.
+///
+/// Both methods follow Gross and Sohl (1990) and use the historical data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopDownMethod {
+    /// Average of the historical proportions: the mean over periods of each
+    /// bottom series' share of the total. Periods with a zero total are
+    /// skipped.
+    AverageHistoricalProportions,
+
+    /// Proportions of the historical averages: each bottom series' mean
+    /// divided by the mean of the total.
+    ProportionsOfHistoricalAverages,
+}
+
+/// The reconciliation matrix G for a given structure and method.
+///
+/// Computing G involves a factorization of an m × m matrix, so build it once
+/// and reuse it when reconciling many forecast vectors over the same structure.
+#[derive(Debug, Clone)]
+pub struct ReconciliationMatrix {
+    /// The m × n matrix G.
+    g: Mat<f64>,
+
+    /// The n × m summation matrix S.
+    s: Mat<f64>,
+
+    /// Method used to compute G, or `None` for user-supplied weights.
+    method: Option<ReconMethod>,
+}
+
+impl ReconciliationMatrix {
+    /// Computes the reconciliation matrix for `s` using `method`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::SingularMatrix`] if $\mathbf{S}'\mathbf{W}^{-1}\mathbf{S}$
+    /// is not positive definite, [`HtsError::Hierarchy`] if a bottom-level
+    /// series has no matching row in S.
+    pub fn new(s: &SummationMatrix, method: ReconMethod) -> Result<Self> {
+        let g = match method {
+            ReconMethod::BottomUp => Self::bottom_up_g(s)?,
+            ReconMethod::Ols => Self::wls_g(&s.matrix, &vec![1.0; s.n_series()])?,
+            ReconMethod::WlsStructural => Self::wls_g(&s.matrix, &s.row_sums())?,
+        };
+
+        Ok(Self {
+            g,
+            s: s.matrix.clone(),
+            method: Some(method),
+        })
+    }
+
+    /// Computes the WLS reconciliation matrix with W = diag(`weights`).
+    ///
+    /// `weights` holds one variance per series, in S row order. Larger
+    /// weights let reconciliation adjust a series more.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `weights.len()` differs from the number of series,
+    /// or if any weight is not strictly positive and finite. Zero weights
+    /// would turn the corresponding series into hard constraints, which this
+    /// method does not support.
+    pub fn wls(s: &SummationMatrix, weights: &[f64]) -> Result<Self> {
+        Self::check_weights(weights, s.n_series(), |i| {
+            format!("'{}'", s.row_labels()[i])
+        })?;
+
+        Ok(Self {
+            g: Self::wls_g(&s.matrix, weights)?,
+            s: s.matrix.clone(),
+            method: None,
+        })
+    }
+
+    /// Computes the WLS reconciliation matrix for the same structure with
+    /// new weights, as [`Self::wls`] would.
+    ///
+    /// Meant for rolling backtests, where S stays fixed and only the
+    /// variance estimates change between windows. G is currently recomputed
+    /// in full; callers get any future reuse of the factorization for free.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::wls`].
+    pub fn with_new_weights(&self, weights: &[f64]) -> Result<Self> {
+        Self::check_weights(weights, self.s.nrows(), |i| format!("{i}"))?;
+
+        Ok(Self {
+            g: Self::wls_g(&self.s, weights)?,
+            s: self.s.clone(),
+            method: None,
+        })
+    }
+
+    /// Checks that there is one positive, finite weight per series, naming
+    /// an offending series with `series`.
+    fn check_weights(weights: &[f64], n: usize, series: impl Fn(usize) -> String) -> Result<()> {
+        if weights.len() != n {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} weights, got {}",
+                weights.len()
+            )));
+        }
+        if let Some((i, w)) = weights
+            .iter()
+            .enumerate()
+            .find(|&(_, &w)| !(w.is_finite() && w > 0.0))
+        {
+            return Err(HtsError::Reconciliation(format!(
+                "Weight for series {} must be positive and finite, got {w}",
+                series(i)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Selects the bottom-level rows: G = [0 | I] up to row ordering.
+    fn bottom_up_g(s: &SummationMatrix) -> Result<Mat<f64>> {
+        let row_index: HashMap<&str, usize> = s
+            .row_labels()
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_str(), i))
+            .collect();
+
+        let mut g = Mat::zeros(s.n_bottom(), s.n_series());
+        for (j, label) in s.col_labels().iter().enumerate() {
+            let &i = row_index.get(label.as_str()).ok_or_else(|| {
+                HtsError::Hierarchy(format!("Bottom-level series '{label}' has no row in S"))
+            })?;
+            g[(j, i)] = 1.0;
+        }
+
+        Ok(g)
+    }
+
+    /// Computes G = (S'W⁻¹S)⁻¹S'W⁻¹ for a diagonal W given by `weights`.
+    fn wls_g(s: &Mat<f64>, weights: &[f64]) -> Result<Mat<f64>> {
+        let (n, m) = s.shape();
+        if weights.len() != n {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} weights, got {}",
+                weights.len()
+            )));
+        }
+
+        // S'W⁻¹ (m × n)
+        let st_winv = Mat::from_fn(m, n, |j, i| s[(i, j)] / weights[i]);
+        let a = &st_winv * s;
+
+        let llt = a
+            .llt(Side::Lower)
+            .map_err(|_| HtsError::SingularMatrix("S'W⁻¹S is not positive definite".to_string()))?;
+
+        Ok(llt.solve(&st_winv))
+    }
+
+    /// Returns the method used to compute G, or `None` for a matrix built
+    /// from user-supplied weights with [`Self::wls`] or
+    /// [`Self::with_new_weights`].
+    pub fn method(&self) -> Option<ReconMethod> {
+        self.method
+    }
+
+    /// Returns a reference to the underlying m × n faer matrix G.
+    pub fn as_faer(&self) -> &Mat<f64> {
+        &self.g
+    }
+
+    /// Reconciles base forecasts for all n series: ỹ = SGŷ.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `y_hat.len()` differs from the number of series.
+    pub fn reconcile(&self, y_hat: &[f64]) -> Result<Vec<f64>> {
+        let n = self.s.nrows();
+        if y_hat.len() != n {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} base forecasts, got {}",
+                y_hat.len()
+            )));
+        }
+
+        let y = Mat::from_fn(n, 1, |i, _| y_hat[i]);
+        let b = &self.g * &y;
+        let y_tilde = &self.s * &b;
+
+        Ok((0..n).map(|i| y_tilde[(i, 0)]).collect())
+    }
+}
+
+/// A data-free view of everything reconciliation needs: the summation matrix,
+/// its row labels, and the aggregation level of each row.
+///
+/// Obtain one from [`HierarchicalTimeSeries::recon_context`](crate::HierarchicalTimeSeries::recon_context),
+/// or build one directly from a `SummationMatrix` to work with the algebra
+/// without any bottom-level data.
+#[derive(Debug, Clone)]
+pub struct ReconContext<'a> {
+    /// The summation matrix.
+    s: &'a SummationMatrix,
+
+    /// Aggregation level of each row of S (0 = total).
+    levels: Vec<usize>,
+}
+
+impl<'a> ReconContext<'a> {
+    /// Creates a context from a summation matrix and the level of each row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `levels` does not have one entry per row of S.
+    pub fn new(s: &'a SummationMatrix, levels: Vec<usize>) -> Result<Self> {
+        if levels.len() != s.n_series() {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {} row levels, got {}",
+                s.n_series(),
+                levels.len()
+            )));
+        }
+
+        Ok(Self { s, levels })
+    }
+
+    /// Returns the summation matrix.
+    pub fn summation_matrix(&self) -> &'a SummationMatrix {
+        self.s
+    }
+
+    /// Returns the series ids, in row order.
+    pub fn row_labels(&self) -> &'a [String] {
+        self.s.row_labels()
+    }
+
+    /// Returns the aggregation level of each row (0 = total).
+    pub fn levels(&self) -> &[usize] {
+        &self.levels
+    }
+
+    /// Computes the reconciliation matrix for `method`.
+    ///
+    /// # Errors
+    ///
+    /// See [`ReconciliationMatrix::new`].
+    pub fn reconciliation_matrix(&self, method: ReconMethod) -> Result<ReconciliationMatrix> {
+        ReconciliationMatrix::new(self.s, method)
+    }
+
+    /// Reconciles base forecasts for all n series (in row order).
+    ///
+    /// # Errors
+    ///
+    /// See [`SummationMatrix::reconcile`].
+    pub fn reconcile(&self, y_hat: &[f64], method: ReconMethod) -> Result<ReconResult<'_>> {
+        let values = self.s.reconcile(y_hat, method)?;
+        Ok(ReconResult {
+            values,
+            labels: self.row_labels(),
+            levels: &self.levels,
+            n_bottom: self.s.n_bottom(),
+        })
+    }
+
+    /// Reconciles base forecasts for all n series (in row order) in two
+    /// stages, switching to bottom-up below `pivot_level`.
+    ///
+    /// First, the series down to `pivot_level` are reconciled with `upper`
+    /// as a hierarchy of their own, in which the pivot-level series are the
+    /// bottom. Then, under each pivot-level series, the bottom-level base
+    /// forecasts are shifted by equal amounts (the least-squares adjustment)
+    /// so that they add up to its reconciled value, and every series below
+    /// the pivot level is summed from them. The upper levels thus get the
+    /// values `upper` gives them, while the levels below keep the shape of
+    /// the bottom-level forecasts. A pivot at the bottom level is the same
+    /// as reconciling everything with `upper`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::{HierarchySpec, HierarchyTree, ReconContext, ReconMethod, SummationMatrix};
+    /// use hts_core::polars::prelude::*;
+    ///
+    /// let df = df! {
+    ///     "State" => ["A", "A", "B", "B"],
+    ///     "Region" => ["AA", "AB", "BA", "BB"],
+    /// }.unwrap();
+    /// let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+    /// let s = SummationMatrix::from_hierarchy(&HierarchyTree::from_dataframe(&df, &spec).unwrap());
+    /// let ctx = ReconContext::new(&s, vec![0, 1, 1, 2, 2, 2, 2]).unwrap();
+    ///
+    /// // OLS between the total and the states, bottom-up below the states
+    /// let y_hat = [11.0, 2.5, 7.0, 1.0, 2.0, 3.0, 4.0];
+    /// let result = ctx.reconcile_staged(ReconMethod::Ols, 1, &y_hat).unwrap();
+    /// let expected = [10.5, 3.0, 7.5, 1.0, 2.0, 3.25, 4.25];
+    /// for (value, expected) in result.values().iter().zip(expected) {
+    ///     assert!((value - expected).abs() < 1e-9);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if `y_hat` does not have n
+    /// values, if no series is at `pivot_level`, if the pivot-level series do
+    /// not split the bottom level into disjoint parts, or if a series above
+    /// the pivot level is not a sum of whole pivot-level series (as can
+    /// happen in grouped structures). Also returns the errors of
+    /// [`ReconciliationMatrix::new`] for `upper`.
+    pub fn reconcile_staged(
+        &self,
+        upper: ReconMethod,
+        pivot_level: usize,
+        y_hat: &[f64],
+    ) -> Result<ReconResult<'_>> {
+        let (n, m) = self.s.shape();
+        if y_hat.len() != n {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} base forecasts, got {}",
+                y_hat.len()
+            )));
+        }
+
+        let rows_at = |keep: fn(usize, usize) -> bool| -> Vec<usize> {
+            (0..n)
+                .filter(|&i| keep(self.levels[i], pivot_level))
+                .collect()
+        };
+        let pivots = rows_at(|level, pivot| level == pivot);
+        let uppers = rows_at(|level, pivot| level < pivot);
+        if pivots.is_empty() {
+            let deepest = self.levels.iter().max().copied().unwrap_or_default();
+            return Err(HtsError::Reconciliation(format!(
+                "No series at pivot level {pivot_level}; levels run from 0 to {deepest}"
+            )));
+        }
+
+        // Pivot-level series owning each bottom-level series
+        let mut owner: Vec<Option<usize>> = vec![None; m];
+        for (k, &i) in pivots.iter().enumerate() {
+            for (j, slot) in owner.iter_mut().enumerate() {
+                if self.s.matrix[(i, j)] == 0.0 {
+                    continue;
+                }
+                if slot.replace(k).is_some() {
+                    return Err(HtsError::Reconciliation(format!(
+                        "Bottom-level series '{}' is under several series of pivot level \
+                         {pivot_level}",
+                        self.s.col_labels[j]
+                    )));
+                }
+            }
+        }
+        let owner: Vec<usize> = owner
+            .iter()
+            .zip(&self.s.col_labels)
+            .map(|(k, id)| {
+                k.ok_or_else(|| {
+                    HtsError::Reconciliation(format!(
+                        "Bottom-level series '{id}' is under no series of pivot level \
+                         {pivot_level}"
+                    ))
+                })
+            })
+            .collect::<Result<_>>()?;
+        let mut sizes = vec![0usize; pivots.len()];
+        for &k in &owner {
+            sizes[k] += 1;
+        }
+
+        // Stage 1: the levels down to the pivot, with the pivot as bottom
+        let (mut rows, mut cols) = (Vec::new(), Vec::new());
+        for (r, &i) in uppers.iter().enumerate() {
+            let mut overlap = vec![0usize; pivots.len()];
+            for (j, &k) in owner.iter().enumerate() {
+                if self.s.matrix[(i, j)] != 0.0 {
+                    overlap[k] += 1;
+                }
+            }
+            for (k, &count) in overlap.iter().enumerate() {
+                if count == sizes[k] {
+                    rows.push(r as u32);
+                    cols.push(k as u32);
+                } else if count > 0 {
+                    return Err(HtsError::Reconciliation(format!(
+                        "Series '{}' is not a sum of whole series of pivot level {pivot_level}",
+                        self.s.row_labels[i]
+                    )));
+                }
+            }
+        }
+        for k in 0..pivots.len() {
+            rows.push((uppers.len() + k) as u32);
+            cols.push(k as u32);
+        }
+        let reduced_rows: Vec<usize> = uppers.iter().chain(&pivots).copied().collect();
+        let reduced = SummationMatrix::from_triplets(
+            &rows,
+            &cols,
+            reduced_rows
+                .iter()
+                .map(|&i| self.s.row_labels[i].clone())
+                .collect(),
+            pivots
+                .iter()
+                .map(|&i| self.s.row_labels[i].clone())
+                .collect(),
+        )?;
+        let reduced_base: Vec<f64> = reduced_rows.iter().map(|&i| y_hat[i]).collect();
+        let reconciled = reduced.reconcile(&reduced_base, upper)?;
+        let pivot_values = &reconciled[uppers.len()..];
+
+        // Stage 2: bottom-up below the pivot, from the adjusted bottom level
+        let mut bottom = y_hat[n - m..].to_vec();
+        let mut shortfall = pivot_values.to_vec();
+        for (&k, value) in owner.iter().zip(&bottom) {
+            shortfall[k] -= value;
+        }
+        for (&k, value) in owner.iter().zip(bottom.iter_mut()) {
+            *value += shortfall[k] / sizes[k] as f64;
+        }
+
+        Ok(ReconResult {
+            values: self.s.aggregate(&bottom),
+            labels: self.row_labels(),
+            levels: &self.levels,
+            n_bottom: m,
+        })
+    }
+}
+
+/// Reconciled values for all series, with accessors by level.
+///
+/// Returned by [`ReconContext::reconcile`]. Values are in S row order, so the
+/// bottom-level series come last.
+#[derive(Debug, Clone)]
+pub struct ReconResult<'a> {
+    /// Reconciled values for all n series.
+    values: Vec<f64>,
+
+    /// Series ids, in row order.
+    labels: &'a [String],
+
+    /// Aggregation level of each row.
+    levels: &'a [usize],
+
+    /// Number of bottom-level series.
+    n_bottom: usize,
+}
+
+impl ReconResult<'_> {
+    /// Returns the reconciled values for all n series, in row order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Consumes the result, returning the values for all n series.
+    pub fn into_values(self) -> Vec<f64> {
+        self.values
+    }
+
+    /// Returns the reconciled bottom-level values, in S column order.
+    pub fn bottom(&self) -> &[f64] {
+        &self.values[self.values.len() - self.n_bottom..]
+    }
+
+    /// Returns the aggregated values (every series above the bottom level),
+    /// in row order.
+    pub fn aggregates(&self) -> &[f64] {
+        &self.values[..self.values.len() - self.n_bottom]
+    }
+
+    /// Returns `(id, value)` pairs for the series at `level` (0 = total).
+    ///
+    /// Returns an empty vector if no series is at that level.
+    pub fn at_level(&self, level: usize) -> Vec<(String, f64)> {
+        self.levels
+            .iter()
+            .zip(self.labels)
+            .zip(&self.values)
+            .filter(|((l, _), _)| **l == level)
+            .map(|((_, id), &value)| (id.clone(), value))
+            .collect()
+    }
+}
+
+/// Convergence controls for iterative reconciliation, such as
+/// [`SummationMatrix::reconcile_non_negative`].
+///
+/// The defaults are `max_iter = 10_000`, `tol = 1e-9` and
+/// `fail_on_max_iter = true`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterOpts {
+    /// Maximum number of iterations.
+    pub max_iter: usize,
+    /// Convergence threshold on the optimality residual, relative to the
+    /// magnitude of the problem (see [`IterResult::residual`]).
+    pub tol: f64,
+    /// Whether reaching `max_iter` without converging is an error. When
+    /// `false`, the last iterate is returned with
+    /// [`IterResult::converged`] unset, for the caller to handle.
+    pub fail_on_max_iter: bool,
+}
+
+impl Default for IterOpts {
+    fn default() -> Self {
+        Self {
+            max_iter: 10_000,
+            tol: 1e-9,
+            fail_on_max_iter: true,
+        }
+    }
+}
+
+/// Outcome of an iterative reconciliation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterResult {
+    /// Reconciled forecasts for all series, in S row order.
+    pub values: Vec<f64>,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Final optimality residual, relative to the magnitude of the problem.
+    pub residual: f64,
+    /// Whether the residual reached [`IterOpts::tol`].
+    pub converged: bool,
+}
+
+/// Forecast accuracy of one aggregation level, before and after
+/// reconciliation. Part of a [`ReconReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelAccuracy {
+    /// Level label, as in [`SummationMatrix::row_level_labels`].
+    pub level: String,
+    /// Number of series of the level with at least one actual value.
+    pub n_series: usize,
+    /// Root mean squared error of the base forecasts.
+    pub rmse_base: f64,
+    /// Root mean squared error of the reconciled forecasts.
+    pub rmse_reconciled: f64,
+    /// Mean absolute scaled error of the base forecasts, or NaN if no series
+    /// of the level has a positive scale.
+    pub mase_base: f64,
+    /// Mean absolute scaled error of the reconciled forecasts, or NaN if no
+    /// series of the level has a positive scale.
+    pub mase_reconciled: f64,
+}
+
+impl LevelAccuracy {
+    /// Percentage reduction of RMSE from base to reconciled; positive when
+    /// reconciliation helped.
+    ///
+    /// Returns NaN when `rmse_base` is zero, as a relative change from an
+    /// exact base forecast is undefined.
+    pub fn rmse_improvement(&self) -> f64 {
+        improvement(self.rmse_base, self.rmse_reconciled)
+    }
+
+    /// Percentage reduction of MASE from base to reconciled; positive when
+    /// reconciliation helped.
+    ///
+    /// Returns NaN when `mase_base` is zero or NaN.
+    pub fn mase_improvement(&self) -> f64 {
+        improvement(self.mase_base, self.mase_reconciled)
+    }
+}
+
+/// Percentage reduction from `base` to `reconciled`, or NaN for a zero base.
+fn improvement(base: f64, reconciled: f64) -> f64 {
+    if base == 0.0 {
+        return f64::NAN;
+    }
+    100.0 * (base - reconciled) / base
+}
+
+/// Per-level accuracy of base and reconciled forecasts against actuals, from
+/// `HierarchicalTimeSeries::reconciliation_report`.
+///
+/// `Display` prints it as a table, one row per level.
+#[derive(Debug, Clone, Default)]
+pub struct ReconReport {
+    /// Accuracy of each level, from the top of the hierarchy down.
+    pub levels: Vec<LevelAccuracy>,
+}
+
+impl std::fmt::Display for ReconReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .levels
+            .iter()
+            .map(|l| l.level.len())
+            .chain([5])
+            .max()
+            .unwrap_or(5);
+
+        writeln!(
+            f,
+            "{:<width$}  {:>10}  {:>10}  {:>8}  {:>10}  {:>10}  {:>8}",
+            "Level", "RMSE base", "RMSE rec", "Δ%", "MASE base", "MASE rec", "Δ%"
+        )?;
+        for l in &self.levels {
+            writeln!(
+                f,
+                "{:<width$}  {:>10.4}  {:>10.4}  {:>8.2}  {:>10.4}  {:>10.4}  {:>8.2}",
+                l.level,
+                l.rmse_base,
+                l.rmse_reconciled,
+                l.rmse_improvement(),
+                l.mase_base,
+                l.mase_reconciled,
+                l.mase_improvement()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hierarchy::{HierarchySpec, HierarchyTree};
+    use polars::prelude::*;
+
+    fn sample_s() -> SummationMatrix {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        SummationMatrix::from_hierarchy(&tree)
+    }
+
+    #[test]
+    fn test_reconciled_forecasts_are_coherent() {
+        let s = sample_s();
+        // Total, A, B, AA, AB, BA, BB
+        let y_hat = [11.0, 2.5, 7.0, 1.0, 2.0, 3.0, 4.0];
+
+        for method in [
+            ReconMethod::BottomUp,
+            ReconMethod::Ols,
+            ReconMethod::WlsStructural,
+        ] {
+            let g = ReconciliationMatrix::new(&s, method).unwrap();
+            let y_tilde = g.reconcile(&y_hat).unwrap();
+
+            assert!((y_tilde[0] - (y_tilde[1] + y_tilde[2])).abs() < 1e-9);
+            assert!((y_tilde[1] - (y_tilde[3] + y_tilde[4])).abs() < 1e-9);
+            assert!((y_tilde[2] - (y_tilde[5] + y_tilde[6])).abs() < 1e-9);
+        }
+
+        let bu = ReconciliationMatrix::new(&s, ReconMethod::BottomUp).unwrap();
+        assert_eq!(
+            bu.reconcile(&y_hat).unwrap(),
+            vec![10.0, 3.0, 7.0, 1.0, 2.0, 3.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn test_coherent_forecasts_are_unchanged() {
+        let s = sample_s();
+        let y = s.aggregate(&[1.0, 2.0, 3.0, 4.0]);
+
+        let ols = ReconciliationMatrix::new(&s, ReconMethod::Ols).unwrap();
+        let y_tilde = ols.reconcile(&y).unwrap();
+
+        for (a, b) in y.iter().zip(&y_tilde) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert!(ols.reconcile(&[1.0]).is_err());
+    }
+
+    #[test]
+    fn test_wls_with_weights() {
+        let s = sample_s();
+        let y_hat = [11.0, 2.5, 7.0, 1.0, 2.0, 3.0, 4.0];
+
+        // Unit weights reproduce OLS
+        let wls = ReconciliationMatrix::wls(&s, &[1.0; 7]).unwrap();
+        let ols = ReconciliationMatrix::new(&s, ReconMethod::Ols).unwrap();
+        for (a, b) in wls
+            .reconcile(&y_hat)
+            .unwrap()
+            .iter()
+            .zip(&ols.reconcile(&y_hat).unwrap())
+        {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert_eq!(wls.method(), None);
+        assert_eq!(ols.method(), Some(ReconMethod::Ols));
+
+        // A tiny variance on the total nearly pins it to its base forecast
+        let mut weights = [1.0; 7];
+        weights[0] = 1e-9;
+        let y_tilde = s.reconcile_wls(&y_hat, &weights).unwrap();
+        assert!((y_tilde[0] - 11.0).abs() < 1e-6);
+        assert!((y_tilde[0] - (y_tilde[1] + y_tilde[2])).abs() < 1e-9);
+
+        assert!(matches!(
+            s.reconcile_wls(&y_hat, &[1.0; 3]),
+            Err(HtsError::Reconciliation(_))
+        ));
+        assert!(s.reconcile_wls(&y_hat, &[0.0; 7]).is_err());
+
+        // Swapping weights on an existing matrix matches building afresh
+        let reweighted = ols.with_new_weights(&weights).unwrap();
+        let direct = ReconciliationMatrix::wls(&s, &weights).unwrap();
+        assert_eq!(reweighted.method(), None);
+        for (a, b) in reweighted
+            .reconcile(&y_hat)
+            .unwrap()
+            .iter()
+            .zip(&direct.reconcile(&y_hat).unwrap())
+        {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert!(ols.with_new_weights(&[1.0; 6]).is_err());
+        assert!(ols.with_new_weights(&[-1.0; 7]).is_err());
+    }
+
+    #[test]
+    fn test_singular_matrix_error() {
+        let s = sample_s();
+        // A zero column makes S'S singular
+        let mut broken = s.clone();
+        broken.matrix = Mat::from_fn(7, 4, |i, j| if j == 3 { 0.0 } else { s.matrix[(i, j)] });
+
+        assert!(matches!(
+            ReconciliationMatrix::new(&broken, ReconMethod::Ols),
+            Err(HtsError::SingularMatrix(_))
+        ));
+        assert!(matches!(
+            ReconciliationMatrix::new(&broken, ReconMethod::WlsStructural),
+            Err(HtsError::SingularMatrix(_))
+        ));
+
+        // Structural weights follow an edit that adds a row
+        let mut grown = s.clone();
+        grown.matrix = Mat::from_fn(8, 4, |i, j| if i == 7 { 1.0 } else { s.matrix[(i, j)] });
+        assert!(ReconciliationMatrix::new(&grown, ReconMethod::WlsStructural).is_ok());
+    }
+
+    #[test]
+    fn test_recon_context() {
+        let s = sample_s();
+        let ctx = ReconContext::new(&s, vec![0, 1, 1, 2, 2, 2, 2]).unwrap();
+
+        assert_eq!(ctx.row_labels()[0], "Total");
+        assert_eq!(ctx.levels()[3], 2);
+        let result = ctx
+            .reconcile(&[11.0, 2.5, 7.0, 1.0, 2.0, 3.0, 4.0], ReconMethod::BottomUp)
+            .unwrap();
+
+        assert_eq!(result.values()[0], 10.0);
+        assert_eq!(result.bottom(), &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(result.aggregates(), &[10.0, 3.0, 7.0]);
+        assert_eq!(result.at_level(0), vec![("Total".to_string(), 10.0)]);
+        assert_eq!(
+            result.at_level(1),
+            vec![("A".to_string(), 3.0), ("B".to_string(), 7.0)]
+        );
+        assert!(result.at_level(5).is_empty());
+        assert_eq!(result.into_values().len(), 7);
+
+        assert!(ReconContext::new(&s, vec![0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_staged() {
+        let s = sample_s();
+        let ctx = ReconContext::new(&s, vec![0, 1, 1, 2, 2, 2, 2]).unwrap();
+        let y_hat = [11.0, 2.5, 7.0, 1.0, 2.0, 3.0, 4.0];
+        let tol = crate::Tolerance::default();
+
+        // OLS on Total = A + B gives A = 3, B = 7.5; B's regions share the
+        // extra 0.5
+        let staged = ctx.reconcile_staged(ReconMethod::Ols, 1, &y_hat).unwrap();
+        let expected = [10.5, 3.0, 7.5, 1.0, 2.0, 3.25, 4.25];
+        for (value, expected) in staged.values().iter().zip(expected) {
+            assert!(tol.approx_eq(*value, expected));
+        }
+        assert!(s.is_coherent(staged.values(), tol));
+
+        // Pivot at the bottom: a plain reconciliation
+        let full = s.reconcile(&y_hat, ReconMethod::WlsStructural).unwrap();
+        let staged = ctx
+            .reconcile_staged(ReconMethod::WlsStructural, 2, &y_hat)
+            .unwrap();
+        for (value, expected) in staged.values().iter().zip(full) {
+            assert!(tol.approx_eq(*value, expected));
+        }
+
+        // Pivot at the total: the bottom level is shifted to the total
+        let staged = ctx.reconcile_staged(ReconMethod::Ols, 0, &y_hat).unwrap();
+        assert_eq!(staged.values()[0], 11.0);
+        assert_eq!(staged.bottom(), &[1.25, 2.25, 3.25, 4.25]);
+
+        assert!(ctx.reconcile_staged(ReconMethod::Ols, 3, &y_hat).is_err());
+        assert!(
+            ctx.reconcile_staged(ReconMethod::Ols, 1, &y_hat[1..])
+                .is_err()
+        );
+
+        // The pivot series must cover the bottom level, and every series
+        // above them must be a sum of whole pivot series
+        let uncovered = ReconContext::new(&s, vec![0, 1, 2, 2, 2, 1, 2]).unwrap();
+        assert!(matches!(
+            uncovered.reconcile_staged(ReconMethod::Ols, 1, &y_hat),
+            Err(HtsError::Reconciliation(_))
+        ));
+        let split = ReconContext::new(&s, vec![0, 1, 2, 0, 2, 1, 1]).unwrap();
+        assert!(matches!(
+            split.reconcile_staged(ReconMethod::Ols, 1, &y_hat),
+            Err(HtsError::Reconciliation(_))
+        ));
+    }
+}
@@ -21,8 +21,37 @@
 //! y = Sb, where y is the n-vector of all series and b is the m-vector
 //! of bottom-level series.
 
+use crate::error::{HtsError, Result};
 use crate::hierarchy::HierarchyTree;
-use faer::Mat;
+use crate::hts::HierarchicalTimeSeries;
+use crate::reconciliation::{IterOpts, IterResult, ReconMethod, ReconciliationMatrix};
+use crate::tolerance::Tolerance;
+use faer::sparse::{SparseColMat, Triplet};
+use faer::{Mat, MatRef};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+/// Magic bytes identifying the binary triplet format.
+const TRIPLET_MAGIC: &[u8; 4] = b"HTSS";
+
+/// Version of the binary triplet format.
+const TRIPLET_VERSION: u32 = 1;
+
+/// Largest number of entries (n × m) of a matrix rebuilt from triplets, 2^28
+/// or 2 GiB of `f64`. S is stored densely, so larger shapes from an untrusted
+/// header are refused before allocating.
+const MAX_TRIPLET_ENTRIES: usize = 1 << 28;
+
+/// Checks that an n × m matrix rebuilt from triplets stays within
+/// [`MAX_TRIPLET_ENTRIES`].
+fn check_triplet_shape(n: usize, m: usize) -> Result<()> {
+    match n.checked_mul(m) {
+        Some(entries) if entries <= MAX_TRIPLET_ENTRIES => Ok(()),
+        _ => Err(HtsError::Hierarchy(format!(
+            "Triplet matrix of {n} × {m} exceeds the limit of {MAX_TRIPLET_ENTRIES} entries"
+        ))),
+    }
+}
 
 /// The summation matrix S where y = Sb.
 ///
@@ -45,6 +74,11 @@ pub struct SummationMatrix {
 
     /// Labels for m bottom-level series (columns).
     pub col_labels: Vec<String>,
+
+    /// Level name of each row: the slash-joined grouping columns of the
+    /// row's level, or the total label for the root. Empty when the level
+    /// structure is unknown (see [`Self::from_triplets`]).
+    pub row_level_labels: Vec<String>,
 }
 
 impl SummationMatrix {
@@ -56,7 +90,10 @@ impl SummationMatrix {
     ///
     /// # Returns
     ///
-    /// A `SummationMatrix` with the correct structure.
+    /// A `SummationMatrix` with the correct structure. Rows follow the tree's
+    /// node order, which lists the bottom-level series last and in column
+    /// order, so the last m rows form the m × m identity (see
+    /// [`Self::bottom_block`]).
     pub fn from_hierarchy(tree: &HierarchyTree) -> Self {
         let n = tree.n_series();
         let m = tree.n_bottom();
@@ -66,6 +103,7 @@ impl SummationMatrix {
 
         let mut row_labels = Vec::with_capacity(n);
         let mut col_labels = Vec::with_capacity(m);
+        let mut row_level_labels = Vec::with_capacity(n);
 
         // Build column labels (bottom-level series)
         for node in tree.bottom_level_nodes() {
@@ -75,6 +113,11 @@ impl SummationMatrix {
         // Build the matrix row by row
         for (row_idx, node) in tree.nodes().iter().enumerate() {
             row_labels.push(node.id.clone());
+            row_level_labels.push(if node.columns.is_empty() {
+                node.id.clone()
+            } else {
+                node.columns.join("/")
+            });
 
             // Set 1.0 for each bottom-level series that aggregates to this node
             for &bottom_idx in &node.aggregates_from {
@@ -86,9 +129,60 @@ impl SummationMatrix {
             matrix,
             row_labels,
             col_labels,
+            row_level_labels,
         }
     }
 
+    /// Builds the temporal summation matrix for `period` high-frequency
+    /// observations per cycle (e.g., 4 for quarterly data within a year).
+    ///
+    /// There is one aggregation order k for each divisor of `period`, and
+    /// each order has `period / k` rows, each summing k consecutive
+    /// observations. Rows are ordered from the coarsest order (k = `period`,
+    /// the annual total) to k = 1, the identity, and are labeled `"k{k}/{j}"`
+    /// with j counted from 1. For `period = 4`, the orders are 4, 2, and 1,
+    /// giving a 7 × 4 matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `period` is zero.
+    pub fn temporal(period: usize) -> Result<Self> {
+        if period == 0 {
+            return Err(HtsError::Hierarchy(
+                "Temporal period must be at least 1".to_string(),
+            ));
+        }
+
+        let orders: Vec<usize> = (1..=period)
+            .rev()
+            .filter(|&k| period.is_multiple_of(k))
+            .collect();
+        let n: usize = orders.iter().map(|k| period / k).sum();
+
+        let mut matrix = Mat::zeros(n, period);
+        let mut row_labels = Vec::with_capacity(n);
+        let mut row_level_labels = Vec::with_capacity(n);
+        let mut row = 0;
+        for &k in &orders {
+            for j in 0..period / k {
+                for col in j * k..(j + 1) * k {
+                    matrix[(row, col)] = 1.0;
+                }
+                row_labels.push(format!("k{k}/{}", j + 1));
+                row_level_labels.push(format!("k{k}"));
+                row += 1;
+            }
+        }
+        let col_labels = row_labels[n - period..].to_vec();
+
+        Ok(Self {
+            matrix,
+            row_labels,
+            col_labels,
+            row_level_labels,
+        })
+    }
+
     /// Aggregates bottom-level values to all levels: y = S * b.
     ///
     /// # Arguments
@@ -124,6 +218,527 @@ impl SummationMatrix {
         (0..n).map(|i| y[(i, 0)]).collect()
     }
 
+    /// Returns the aggregation y = S b as a closure, for code that composes
+    /// functions.
+    ///
+    /// Stable Rust does not allow implementing `Fn` for a type, so this
+    /// borrows S into a closure instead; it behaves exactly like
+    /// [`Self::aggregate`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hts_core::SummationMatrix;
+    ///
+    /// let s = SummationMatrix::from_triplets(
+    ///     &[0, 0, 1, 2],
+    ///     &[0, 1, 0, 1],
+    ///     vec!["Total".into(), "A".into(), "B".into()],
+    ///     vec!["A".into(), "B".into()],
+    /// )
+    /// .unwrap();
+    ///
+    /// let aggregate = s.to_operator();
+    /// assert_eq!(aggregate(&[1.0, 2.0]), vec![3.0, 1.0, 2.0]);
+    /// ```
+    pub fn to_operator(&self) -> impl Fn(&[f64]) -> Vec<f64> + '_ {
+        move |bottom_values| self.aggregate(bottom_values)
+    }
+
+    /// Aggregates only the bottom-level series flagged in `included`.
+    ///
+    /// Excluded series count as zero, so the result is what every series
+    /// would be without them, e.g. to simulate dropping a region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bottom_values` or `included` do not have m entries.
+    pub fn aggregate_subset(&self, bottom_values: &[f64], included: &[bool]) -> Vec<f64> {
+        assert_eq!(
+            included.len(),
+            self.n_bottom(),
+            "Expected {} inclusion flags, got {}",
+            self.n_bottom(),
+            included.len()
+        );
+
+        let masked: Vec<f64> = bottom_values
+            .iter()
+            .zip(included)
+            .map(|(&v, &keep)| if keep { v } else { 0.0 })
+            .collect();
+        self.aggregate(&masked)
+    }
+
+    /// Aggregates bottom-level values without checking their length.
+    ///
+    /// Same as [`Self::aggregate`], for tight loops where the caller
+    /// guarantees that `bottom_values` holds exactly m values in column order.
+    /// The length is only checked in debug builds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bottom_values` is shorter than m. Extra values are ignored
+    /// in release builds.
+    pub fn aggregate_unchecked(&self, bottom_values: &[f64]) -> Vec<f64> {
+        debug_assert_eq!(bottom_values.len(), self.n_bottom());
+
+        let b =
+            MatRef::from_column_major_slice(&bottom_values[..self.n_bottom()], self.n_bottom(), 1);
+        let y = &self.matrix * b;
+        y.col(0).iter().copied().collect()
+    }
+
+    /// Aggregates many bottom-level vectors at once: Y = S * B.
+    ///
+    /// `bottom` is an m × T matrix with one column per period (or scenario);
+    /// the result is the n × T matrix of all series.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bottom.nrows() != self.n_bottom()`.
+    pub fn aggregate_batch(&self, bottom: MatRef<'_, f64>) -> Mat<f64> {
+        assert_eq!(
+            bottom.nrows(),
+            self.n_bottom(),
+            "Expected {} bottom rows, got {}",
+            self.n_bottom(),
+            bottom.nrows()
+        );
+
+        self.aggregate_batch_unchecked(bottom)
+    }
+
+    /// Aggregates many bottom-level vectors at once without checking their
+    /// shape.
+    ///
+    /// Same as [`Self::aggregate_batch`], with the row count only checked in
+    /// debug builds.
+    pub fn aggregate_batch_unchecked(&self, bottom: MatRef<'_, f64>) -> Mat<f64> {
+        debug_assert_eq!(bottom.nrows(), self.n_bottom());
+
+        &self.matrix * bottom
+    }
+
+    /// Returns true if `y`, given for all n series in row order, is coherent:
+    /// every row equals the sum of the bottom-level values it aggregates,
+    /// within `tol`.
+    ///
+    /// The bottom-level values are read from the last m entries of `y` (see
+    /// [`Self::bottom_block`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y.len() != self.n_series()`.
+    pub fn is_coherent(&self, y: &[f64], tol: Tolerance) -> bool {
+        assert_eq!(
+            y.len(),
+            self.n_series(),
+            "Expected {} values, got {}",
+            self.n_series(),
+            y.len()
+        );
+
+        let (n, m) = self.shape();
+        self.aggregate(&y[n - m..])
+            .iter()
+            .zip(y)
+            .all(|(&expected, &actual)| tol.approx_eq(expected, actual))
+    }
+
+    /// Returns how far `y`, given for all n series in row order, is from
+    /// coherent: the largest absolute difference between a row and the sum
+    /// of the bottom-level values it aggregates.
+    ///
+    /// The bottom-level values are read from the last m entries of `y`, as in
+    /// [`Self::is_coherent`], so bottom rows never contribute. A coherent
+    /// vector has a residual of zero up to rounding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y.len() != self.n_series()`.
+    pub fn coherence_residual(&self, y: &[f64]) -> f64 {
+        assert_eq!(
+            y.len(),
+            self.n_series(),
+            "Expected {} values, got {}",
+            self.n_series(),
+            y.len()
+        );
+
+        let (n, m) = self.shape();
+        self.aggregate(&y[n - m..])
+            .iter()
+            .zip(y)
+            .map(|(expected, actual)| (expected - actual).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Checks that the matrix has the shape of a summation matrix.
+    ///
+    /// Verifies that (a) every bottom-level series j has a row selecting it
+    /// alone (the unit vector eⱼ, so S contains the m × m identity), (b) the
+    /// first row, the total, is all ones, and (c) no two rows of the same
+    /// level are equal. Rows of different levels may be: a node with a single
+    /// child sums the same series as its child. Without level labels (see
+    /// [`Self::row_level_labels`]) no two rows may be equal.
+    ///
+    /// Matrices built by [`Self::from_hierarchy`] always pass; this catches
+    /// mistakes in matrices built externally, e.g. with
+    /// [`Self::from_triplets`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Hierarchy`] describing the first violation found.
+    pub fn verify_structure(&self) -> Result<()> {
+        let (n, m) = self.shape();
+        let label = |i: usize| self.row_labels.get(i).map_or("?", String::as_str);
+
+        let mut unit_rows = vec![false; m];
+        let mut seen = HashSet::with_capacity(n);
+        for i in 0..n {
+            let level = self.row_level_labels.get(i).map_or("", String::as_str);
+            let row: Vec<u64> = (0..m).map(|j| self.matrix[(i, j)].to_bits()).collect();
+            let nonzero: Vec<usize> = (0..m).filter(|&j| self.matrix[(i, j)] != 0.0).collect();
+            if let [j] = nonzero[..]
+                && self.matrix[(i, j)] == 1.0
+            {
+                unit_rows[j] = true;
+            }
+            if !seen.insert((level, row)) {
+                return Err(HtsError::Hierarchy(format!(
+                    "Row {i} ('{}') duplicates an earlier row of its level",
+                    label(i)
+                )));
+            }
+        }
+
+        if let Some(j) = unit_rows.iter().position(|&found| !found) {
+            return Err(HtsError::Hierarchy(format!(
+                "No row selects bottom-level series {j} ('{}') alone",
+                self.col_labels.get(j).map_or("?", String::as_str)
+            )));
+        }
+        if n > 0 && (0..m).any(|j| self.matrix[(0, j)] != 1.0) {
+            return Err(HtsError::Hierarchy(format!(
+                "The first row ('{}') is not all ones, so it is not a total",
+                label(0)
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that an in-sample residual matrix fits this structure.
+    ///
+    /// Covariance-based reconciliation (e.g., MinT) expects one row per
+    /// series, in row order, and one column per period. Validating up front
+    /// gives a descriptive error instead of a failure deep in the solver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if `residuals` does not have
+    /// `n_series()` rows, or has fewer than two columns, too few to estimate
+    /// a covariance.
+    pub fn validate_residuals(&self, residuals: &Mat<f64>) -> Result<()> {
+        if residuals.nrows() != self.n_series() {
+            return Err(HtsError::Reconciliation(format!(
+                "Residual matrix has {} rows but the hierarchy has {} series",
+                residuals.nrows(),
+                self.n_series()
+            )));
+        }
+        if residuals.ncols() < 2 {
+            return Err(HtsError::Reconciliation(format!(
+                "Residual matrix has {} period(s); at least 2 are needed to estimate a \
+                 covariance",
+                residuals.ncols()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reconciles base forecasts for all n series (in row order).
+    ///
+    /// This computes the reconciliation matrix on every call; build a
+    /// [`ReconciliationMatrix`] once to reconcile many vectors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `y_hat.len() != self.n_series()` or if the
+    /// reconciliation matrix cannot be computed.
+    pub fn reconcile(&self, y_hat: &[f64], method: ReconMethod) -> Result<Vec<f64>> {
+        ReconciliationMatrix::new(self, method)?.reconcile(y_hat)
+    }
+
+    /// Reconciles base forecasts by weighted least squares with
+    /// W = diag(`weights`).
+    ///
+    /// `weights` holds one variance estimate per series, in row order. See
+    /// [`ReconciliationMatrix::wls`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `y_hat` or `weights` do not have one entry per
+    /// series, or if any weight is not strictly positive and finite.
+    pub fn reconcile_wls(&self, y_hat: &[f64], weights: &[f64]) -> Result<Vec<f64>> {
+        ReconciliationMatrix::wls(self, weights)?.reconcile(y_hat)
+    }
+
+    /// Reconciles quantile forecasts, one quantile level at a time.
+    ///
+    /// `base` pairs each quantile level in [0, 1] with the base forecasts of
+    /// all n series at that level, in row order. Every level is reconciled
+    /// independently with the same reconciliation matrix, a common
+    /// approximation to probabilistic reconciliation. The output keeps the
+    /// order of `base`.
+    ///
+    /// Independent reconciliation can make a series' quantiles cross. With
+    /// `sort` set, each bottom-level series' reconciled values are re-sorted
+    /// to be non-decreasing in the quantile level before aggregating, so the
+    /// result is monotone for every series and still coherent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if a level is outside [0, 1] or a
+    /// forecast vector does not have one entry per series, and any error of
+    /// [`ReconciliationMatrix::new`].
+    pub fn reconcile_quantiles(
+        &self,
+        base: &[(f64, Vec<f64>)],
+        method: ReconMethod,
+        sort: bool,
+    ) -> Result<Vec<(f64, Vec<f64>)>> {
+        if let Some((level, _)) = base.iter().find(|(q, _)| !(0.0..=1.0).contains(q)) {
+            return Err(HtsError::Reconciliation(format!(
+                "Quantile level {level} is outside [0, 1]"
+            )));
+        }
+        let recon = ReconciliationMatrix::new(self, method)?;
+        if !sort {
+            return base
+                .iter()
+                .map(|(q, y_hat)| Ok((*q, recon.reconcile(y_hat)?)))
+                .collect();
+        }
+
+        // Bottom-level forecasts of every quantile, as columns
+        let (n, m) = self.shape();
+        if let Some((_, y_hat)) = base.iter().find(|(_, y_hat)| y_hat.len() != n) {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} base forecasts, got {}",
+                y_hat.len()
+            )));
+        }
+        let y_hat = Mat::from_fn(n, base.len(), |i, k| base[k].1[i]);
+        let mut bottom = recon.as_faer() * &y_hat;
+
+        // Sort each bottom series along the quantile levels
+        let mut order: Vec<usize> = (0..base.len()).collect();
+        order.sort_by(|&a, &b| base[a].0.total_cmp(&base[b].0));
+        for j in 0..m {
+            let mut values: Vec<f64> = order.iter().map(|&k| bottom[(j, k)]).collect();
+            values.sort_by(f64::total_cmp);
+            for (&k, value) in order.iter().zip(values) {
+                bottom[(j, k)] = value;
+            }
+        }
+
+        let y_tilde = self.aggregate_batch(bottom.as_ref());
+        Ok(base
+            .iter()
+            .enumerate()
+            .map(|(k, (q, _))| (*q, (0..n).map(|i| y_tilde[(i, k)]).collect()))
+            .collect())
+    }
+
+    /// Reconciles base forecasts by weighted least squares with
+    /// W = diag(`weights`), keeping every bottom-level value non-negative.
+    ///
+    /// Useful for counts and sales, where plain WLS can produce negative
+    /// bottom-level forecasts. Pass all-ones weights for OLS. The
+    /// constrained problem is solved by accelerated projected gradient,
+    /// starting from the unconstrained solution clipped at zero, until the
+    /// optimality residual max |b − max(0, b − ∇f(b))|, divided by
+    /// max(1, |S'W⁻¹ŷ|∞), falls below `opts.tol`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`ReconciliationMatrix::wls`], and
+    /// [`HtsError::Reconciliation`] if `y_hat` does not have one entry per
+    /// series or, with [`IterOpts::fail_on_max_iter`], if the solver does not
+    /// converge within `opts.max_iter` iterations.
+    pub fn reconcile_non_negative(
+        &self,
+        y_hat: &[f64],
+        weights: &[f64],
+        opts: IterOpts,
+    ) -> Result<IterResult> {
+        let (n, m) = self.shape();
+        let recon = ReconciliationMatrix::wls(self, weights)?;
+        if y_hat.len() != n {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} base forecasts, got {}",
+                y_hat.len()
+            )));
+        }
+
+        // f(b) = ½(ŷ − Sb)'W⁻¹(ŷ − Sb), with gradient Ab − c
+        let st_winv = Mat::from_fn(m, n, |j, i| self.matrix[(i, j)] / weights[i]);
+        let a = &st_winv * &self.matrix;
+        let y = Mat::from_fn(n, 1, |i, _| y_hat[i]);
+        let c = &st_winv * &y;
+        let scale = (0..m).map(|j| c[(j, 0)].abs()).fold(1.0, f64::max);
+
+        // Gershgorin bound on the largest eigenvalue of A
+        let lipschitz = (0..m)
+            .map(|i| (0..m).map(|j| a[(i, j)].abs()).sum::<f64>())
+            .fold(0.0, f64::max);
+        let step = 1.0 / lipschitz;
+
+        let residual = |b: &Mat<f64>| {
+            let grad = &a * b - &c;
+            (0..m)
+                .map(|j| (b[(j, 0)] - (b[(j, 0)] - grad[(j, 0)]).max(0.0)).abs())
+                .fold(0.0, f64::max)
+                / scale
+        };
+
+        let unconstrained = recon.as_faer() * &y;
+        let mut b = Mat::from_fn(m, 1, |j, _| unconstrained[(j, 0)].max(0.0));
+        let mut z = b.clone();
+        let mut t = 1.0_f64;
+        let mut iterations = 0;
+        let mut r = residual(&b);
+
+        while r > opts.tol && iterations < opts.max_iter {
+            let grad = &a * &z - &c;
+            let next = Mat::from_fn(m, 1, |j, _| (z[(j, 0)] - step * grad[(j, 0)]).max(0.0));
+            let t_next = (1.0 + (1.0 + 4.0 * t * t).sqrt()) / 2.0;
+            let momentum = (t - 1.0) / t_next;
+            z = Mat::from_fn(m, 1, |j, _| {
+                next[(j, 0)] + momentum * (next[(j, 0)] - b[(j, 0)])
+            });
+            b = next;
+            t = t_next;
+            iterations += 1;
+            r = residual(&b);
+        }
+
+        let converged = r <= opts.tol;
+        if !converged && opts.fail_on_max_iter {
+            return Err(HtsError::Reconciliation(format!(
+                "Non-negative reconciliation did not converge after {iterations} iterations \
+                 (residual {r:.3e}, tolerance {:.3e})",
+                opts.tol
+            )));
+        }
+
+        let bottom: Vec<f64> = (0..m).map(|j| b[(j, 0)]).collect();
+        Ok(IterResult {
+            values: self.aggregate(&bottom),
+            iterations,
+            residual: r,
+            converged,
+        })
+    }
+
+    /// Reconciles base forecasts by OLS while holding selected series fixed.
+    ///
+    /// Each anchor names a series by row label and the value it must take,
+    /// e.g. a published national total. The result is the coherent vector
+    /// S b closest to `y_hat` in the least-squares sense among those that
+    /// match every anchor exactly, found by solving the KKT system of the
+    /// equality-constrained problem.
+    ///
+    /// Redundant but consistent anchors (say, a total and all of its
+    /// children, adding up) are accepted.
+    ///
+    /// The KKT system is solved directly rather than iteratively, so there
+    /// is no convergence to control; see [`Self::reconcile_non_negative`]
+    /// for an iterative variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtsError::Reconciliation`] if `y_hat` does not have one
+    /// entry per series, or if the anchors cannot all hold in a coherent
+    /// vector (e.g., children that do not add up to an anchored parent), and
+    /// [`HtsError::Hierarchy`] if an anchor names an unknown series.
+    pub fn reconcile_with_anchors(
+        &self,
+        y_hat: &[f64],
+        anchors: &[(String, f64)],
+    ) -> Result<Vec<f64>> {
+        let (n, m) = self.shape();
+        if y_hat.len() != n {
+            return Err(HtsError::Reconciliation(format!(
+                "Expected {n} base forecasts, got {}",
+                y_hat.len()
+            )));
+        }
+
+        let rows = anchors
+            .iter()
+            .map(|(id, _)| {
+                self.row_labels
+                    .iter()
+                    .position(|label| label == id)
+                    .ok_or_else(|| HtsError::Hierarchy(format!("Unknown anchored series '{id}'")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let k = rows.len();
+
+        // KKT system: [S'S  S_A'; S_A  0] [b; λ] = [S'ŷ; a]
+        let y = MatRef::from_column_major_slice(y_hat, n, 1);
+        let sts = self.matrix.transpose() * &self.matrix;
+        let sty = self.matrix.transpose() * y;
+        let kkt = Mat::from_fn(m + k, m + k, |i, j| match (i < m, j < m) {
+            (true, true) => sts[(i, j)],
+            (true, false) => self.matrix[(rows[j - m], i)],
+            (false, true) => self.matrix[(rows[i - m], j)],
+            (false, false) => 0.0,
+        });
+        let rhs = Mat::from_fn(
+            m + k,
+            1,
+            |i, _| {
+                if i < m { sty[(i, 0)] } else { anchors[i - m].1 }
+            },
+        );
+
+        // Redundant anchors make the system singular, so solve it in the
+        // least-squares sense through the SVD, dropping negligible singular
+        // values, and check the anchors afterwards
+        let svd = kkt
+            .svd()
+            .map_err(|_| HtsError::Reconciliation("SVD of the KKT system failed".to_string()))?;
+        let sv = svd.S().column_vector();
+        let sv_cutoff = (m + k) as f64 * f64::EPSILON * sv.iter().fold(0.0_f64, |a, &b| a.max(b));
+        let ut_rhs = svd.U().transpose() * &rhs;
+        let scaled = Mat::from_fn(m + k, 1, |i, _| {
+            if sv[i] > sv_cutoff {
+                ut_rhs[(i, 0)] / sv[i]
+            } else {
+                0.0
+            }
+        });
+        let solution = svd.V() * scaled;
+        let y_tilde = self.aggregate(&(0..m).map(|i| solution[(i, 0)]).collect::<Vec<_>>());
+
+        let tol = Tolerance::default();
+        for ((id, value), &row) in anchors.iter().zip(&rows) {
+            if !tol.approx_eq(y_tilde[row], *value) {
+                return Err(HtsError::Reconciliation(format!(
+                    "Anchors are inconsistent with coherence: '{id}' must be {value} but can be \
+                     at best {}",
+                    y_tilde[row]
+                )));
+            }
+        }
+
+        Ok(y_tilde)
+    }
+
     /// Returns the matrix dimensions (n_series, n_bottom).
     pub fn shape(&self) -> (usize, usize) {
         (self.matrix.nrows(), self.matrix.ncols())
@@ -139,6 +754,78 @@ impl SummationMatrix {
         self.matrix.ncols()
     }
 
+    /// Returns the number of aggregation constraints, n - m.
+    ///
+    /// Each aggregated series contributes one linear constraint tying it to
+    /// the bottom-level series.
+    pub fn n_constraints(&self) -> usize {
+        self.n_series() - self.n_bottom()
+    }
+
+    /// Returns the row sums of S, S1: the number of bottom-level series
+    /// under each series, in row order.
+    ///
+    /// These are the weights of [`ReconMethod::WlsStructural`] and the leaf
+    /// count of each aggregate. They are computed from the current
+    /// [`Self::matrix`], so they follow any edit of it.
+    pub fn row_sums(&self) -> Vec<f64> {
+        (0..self.matrix.nrows())
+            .map(|i| self.matrix.row(i).iter().sum())
+            .collect()
+    }
+
+    /// Returns the column sums of S: the number of series, its own row
+    /// included, that each bottom-level series enters, in column order.
+    ///
+    /// In a hierarchy every bottom-level series enters one series per level.
+    /// Computed from the current [`Self::matrix`], like [`Self::row_sums`].
+    pub fn col_sums(&self) -> Vec<f64> {
+        (0..self.matrix.ncols())
+            .map(|j| self.matrix.col(j).iter().sum())
+            .collect()
+    }
+
+    /// Returns the numerical rank of S, computed from its singular values.
+    ///
+    /// Singular values below `max(n, m) * ε * σ_max` are treated as zero.
+    /// A matrix built by [`Self::from_hierarchy`] contains the m × m identity
+    /// among its rows, so its rank is always m; a smaller rank means some
+    /// bottom-level series are linear combinations of others, which indicates
+    /// a malformed matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the singular value decomposition fails to converge.
+    pub fn rank(&self) -> usize {
+        if self.n_bottom() == 0 {
+            return 0;
+        }
+
+        let sv = self
+            .matrix
+            .singular_values()
+            .expect("SVD of a summation matrix converges");
+        let (n, m) = self.shape();
+        let tol = n.max(m) as f64 * f64::EPSILON * sv.first().copied().unwrap_or(0.0);
+
+        sv.iter().filter(|&&s| s > tol).count()
+    }
+
+    /// Returns the m × m block of bottom-level rows, the last m rows of S.
+    ///
+    /// For a matrix built by [`Self::from_hierarchy`] this is the identity,
+    /// so S = [A' | I']' with A the [`Self::aggregation_block`].
+    pub fn bottom_block(&self) -> MatRef<'_, f64> {
+        let (n, m) = self.shape();
+        self.matrix.as_ref().subrows(n - m, m)
+    }
+
+    /// Returns the (n - m) × m block of aggregated rows, the first n - m rows
+    /// of S.
+    pub fn aggregation_block(&self) -> MatRef<'_, f64> {
+        self.matrix.as_ref().subrows(0, self.n_constraints())
+    }
+
     /// Returns a reference to the underlying faer matrix.
     pub fn as_faer(&self) -> &Mat<f64> {
         &self.matrix
@@ -154,6 +841,192 @@ impl SummationMatrix {
         &self.col_labels
     }
 
+    /// Returns the level name of each row, e.g. `"State/Purpose"`.
+    ///
+    /// The root is labeled with its id, the spec's total label. Empty when
+    /// the matrix was built from triplets.
+    pub fn row_level_labels(&self) -> &[String] {
+        &self.row_level_labels
+    }
+
+    /// Returns the (row, column) coordinates of the nonzero entries of S.
+    ///
+    /// Since S is binary, these coordinates fully describe the matrix. Entries
+    /// are listed in row-major order.
+    pub fn to_triplets(&self) -> (Vec<u32>, Vec<u32>) {
+        let (n, m) = self.shape();
+        let mut rows = Vec::new();
+        let mut cols = Vec::new();
+
+        for i in 0..n {
+            for j in 0..m {
+                if self.matrix[(i, j)] != 0.0 {
+                    rows.push(i as u32);
+                    cols.push(j as u32);
+                }
+            }
+        }
+
+        (rows, cols)
+    }
+
+    /// Returns S as a faer sparse column-major matrix.
+    ///
+    /// Only the nonzero entries are stored, so the result can be handed to
+    /// faer's sparse decompositions and solvers without going through the
+    /// dense representation.
+    pub fn to_sparse_col_mat(&self) -> SparseColMat<usize, f64> {
+        let (n, m) = self.shape();
+        let (rows, cols) = self.to_triplets();
+        let entries: Vec<Triplet<usize, usize, f64>> = rows
+            .iter()
+            .zip(&cols)
+            .map(|(&i, &j)| {
+                Triplet::new(
+                    i as usize,
+                    j as usize,
+                    self.matrix[(i as usize, j as usize)],
+                )
+            })
+            .collect();
+
+        SparseColMat::try_new_from_triplets(n, m, &entries)
+            .expect("triplets of S are in bounds and unique")
+    }
+
+    /// Rebuilds a summation matrix from the coordinates of its ones.
+    ///
+    /// This is the inverse of [`Self::to_triplets`]. The shape is taken from
+    /// the number of labels. Triplets carry no level information, so
+    /// [`Self::row_level_labels`] is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `rows` and `cols` differ in length, a coordinate
+    /// is out of bounds, or the matrix would have more than 2^28 entries.
+    pub fn from_triplets(
+        rows: &[u32],
+        cols: &[u32],
+        row_labels: Vec<String>,
+        col_labels: Vec<String>,
+    ) -> Result<Self> {
+        if rows.len() != cols.len() {
+            return Err(HtsError::Hierarchy(format!(
+                "Triplet coordinates differ in length: {} rows, {} columns",
+                rows.len(),
+                cols.len()
+            )));
+        }
+
+        let (n, m) = (row_labels.len(), col_labels.len());
+        check_triplet_shape(n, m)?;
+        let mut matrix = Mat::zeros(n, m);
+        for (&i, &j) in rows.iter().zip(cols) {
+            let (i, j) = (i as usize, j as usize);
+            if i >= n || j >= m {
+                return Err(HtsError::Hierarchy(format!(
+                    "Triplet ({i}, {j}) out of bounds for a {n} × {m} matrix"
+                )));
+            }
+            matrix[(i, j)] = 1.0;
+        }
+
+        Ok(Self {
+            matrix,
+            row_labels,
+            col_labels,
+            row_level_labels: Vec::new(),
+        })
+    }
+
+    /// Writes S in a compact little-endian binary format.
+    ///
+    /// The layout is: the magic bytes `HTSS`, a `u32` format version, `u32`
+    /// n and m, a `u64` count of nonzero entries, the row and then column
+    /// coordinates as `u32`, and finally the n row labels followed by the
+    /// m column labels, each as a `u32` byte length and UTF-8 bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_triplets<W: Write>(&self, mut writer: W) -> Result<()> {
+        let (rows, cols) = self.to_triplets();
+        let (n, m) = self.shape();
+
+        writer.write_all(TRIPLET_MAGIC)?;
+        writer.write_all(&TRIPLET_VERSION.to_le_bytes())?;
+        writer.write_all(&(n as u32).to_le_bytes())?;
+        writer.write_all(&(m as u32).to_le_bytes())?;
+        writer.write_all(&(rows.len() as u64).to_le_bytes())?;
+        for idx in rows.iter().chain(&cols) {
+            writer.write_all(&idx.to_le_bytes())?;
+        }
+        for label in self.row_labels.iter().chain(&self.col_labels) {
+            writer.write_all(&(label.len() as u32).to_le_bytes())?;
+            writer.write_all(label.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a summation matrix written by [`Self::write_triplets`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the data is not in the expected
+    /// format, and [`HtsError::Hierarchy`] if the header declares a matrix of
+    /// more than 2^28 entries, like [`Self::from_triplets`].
+    pub fn read_triplets<R: Read>(mut reader: R) -> Result<Self> {
+        fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+            let mut buf = [0; 4];
+            reader.read_exact(&mut buf)?;
+            Ok(u32::from_le_bytes(buf))
+        }
+        let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != TRIPLET_MAGIC {
+            return Err(invalid("Not a summation matrix triplet file").into());
+        }
+        let version = read_u32(&mut reader)?;
+        if version != TRIPLET_VERSION {
+            return Err(invalid(&format!("Unsupported triplet format version {version}")).into());
+        }
+
+        let n = read_u32(&mut reader)? as usize;
+        let m = read_u32(&mut reader)? as usize;
+        let mut nnz = [0; 8];
+        reader.read_exact(&mut nnz)?;
+        let nnz = u64::from_le_bytes(nnz) as usize;
+        check_triplet_shape(n, m)?;
+
+        // The sizes are untrusted: grow the buffers as the input is read
+        // rather than allocating them up front, so a corrupt header fails at
+        // the end of the input instead of exhausting memory.
+        let mut rows = Vec::new();
+        for _ in 0..nnz {
+            rows.push(read_u32(&mut reader)?);
+        }
+        let mut cols = Vec::new();
+        for _ in 0..nnz {
+            cols.push(read_u32(&mut reader)?);
+        }
+
+        let mut labels = Vec::new();
+        for _ in 0..n + m {
+            let len = read_u32(&mut reader)? as u64;
+            let mut buf = Vec::new();
+            if (&mut reader).take(len).read_to_end(&mut buf)? as u64 != len {
+                return Err(invalid("Truncated triplet label").into());
+            }
+            labels.push(String::from_utf8(buf).map_err(|e| invalid(&e.to_string()))?);
+        }
+        let col_labels = labels.split_off(n);
+
+        Self::from_triplets(&rows, &cols, labels, col_labels)
+    }
+
     /// Converts to a 2D Vec for inspection/debugging.
     pub fn to_vec(&self) -> Vec<Vec<f64>> {
         let (n, m) = self.shape();
@@ -163,12 +1036,29 @@ impl SummationMatrix {
     }
 }
 
+/// Clones the summation matrix of a hierarchical time series, the same
+/// matrix [`SummationMatrix::from_hierarchy`] builds from its tree.
+impl From<&HierarchicalTimeSeries> for SummationMatrix {
+    fn from(hts: &HierarchicalTimeSeries) -> Self {
+        hts.summation_matrix().clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::hierarchy::{HierarchySpec, HierarchyTree};
     use polars::prelude::*;
 
+    #[test]
+    fn test_from_hts() {
+        let hts = crate::testing::random_hierarchy(&[2, 2], &[], 1, 3);
+        let s = SummationMatrix::from(&hts);
+        let from_tree = SummationMatrix::from_hierarchy(hts.hierarchy_tree());
+        assert_eq!(s.row_labels, from_tree.row_labels);
+        assert_eq!(s.to_vec(), from_tree.to_vec());
+    }
+
     #[test]
     fn test_summation_matrix_shape() {
         let df = df! {
@@ -217,6 +1107,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_identity_at_bottom() {
         // The bottom portion of S should be an identity matrix
         // We use unsorted input to verify that the implementation correctly sorts it
@@ -263,4 +1154,499 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_constraints_and_rank() {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+            "Value" => [1.0, 2.0, 3.0, 4.0],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+
+        // Total + 2 states aggregate 4 regions
+        assert_eq!(s.n_constraints(), 3);
+        assert_eq!(s.rank(), 4);
+
+        // Duplicated bottom column makes S rank deficient
+        let mut broken = s.clone();
+        broken.matrix = Mat::from_fn(7, 2, |i, _| s.matrix[(i, 0)]);
+        assert_eq!(broken.rank(), 1);
+    }
+
+    #[test]
+    fn test_row_level_labels() {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+            "Purpose" => ["X", "Y", "X", "Y"],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::new(
+            vec!["State".into(), "Region".into()],
+            vec!["Purpose".into()],
+        )
+        .with_total_label("All");
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+
+        let labels = s.row_level_labels();
+        assert_eq!(labels.len(), s.n_series());
+        assert_eq!(labels[0], "All");
+        assert_eq!(labels[s.n_series() - 1], "State/Region/Purpose");
+        for (label, node) in labels.iter().zip(tree.nodes()) {
+            assert_eq!(label.split('/').count(), node.columns.len().max(1));
+        }
+        assert!(labels.iter().any(|l| l == "State/Purpose"));
+    }
+
+    #[test]
+    fn test_aggregate_unchecked_and_batch() {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+
+        let b = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(s.aggregate_unchecked(&b), s.aggregate(&b));
+
+        // Two periods as columns
+        let batch = Mat::from_fn(4, 2, |i, t| b[i] * (t + 1) as f64);
+        let y = s.aggregate_batch(batch.as_ref());
+        assert_eq!((y.nrows(), y.ncols()), (7, 2));
+        for t in 0..2 {
+            let column: Vec<f64> = (0..4).map(|i| batch[(i, t)]).collect();
+            let expected = s.aggregate(&column);
+            for (i, value) in expected.iter().enumerate() {
+                assert_eq!(y[(i, t)], *value);
+            }
+        }
+        assert_eq!(s.aggregate_batch_unchecked(batch.as_ref()), y);
+    }
+
+    #[test]
+    fn test_temporal() {
+        let s = SummationMatrix::temporal(4).unwrap();
+        assert_eq!(s.shape(), (7, 4));
+        assert_eq!(s.row_labels()[0], "k4/1");
+        assert_eq!(s.row_labels()[2], "k2/2");
+        assert_eq!(s.col_labels(), &["k1/1", "k1/2", "k1/3", "k1/4"]);
+        assert_eq!(
+            s.aggregate(&[1.0, 2.0, 3.0, 4.0]),
+            vec![10.0, 3.0, 7.0, 1.0, 2.0, 3.0, 4.0]
+        );
+
+        // Monthly: orders 12, 6, 4, 3, 2, 1
+        assert_eq!(SummationMatrix::temporal(12).unwrap().n_series(), 28);
+        assert_eq!(SummationMatrix::temporal(1).unwrap().shape(), (1, 1));
+        assert!(SummationMatrix::temporal(0).is_err());
+    }
+
+    #[test]
+    fn test_to_sparse_col_mat() {
+        let s = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["Total".into(), "A".into(), "B".into()],
+            vec!["A".into(), "B".into()],
+        )
+        .unwrap();
+
+        let sparse = s.to_sparse_col_mat();
+        assert_eq!((sparse.nrows(), sparse.ncols()), s.shape());
+        assert_eq!(sparse.compute_nnz(), 4);
+        assert_eq!(sparse.to_dense(), *s.as_faer());
+    }
+
+    #[test]
+    fn test_aggregate_subset() {
+        let s = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["Total".into(), "A".into(), "B".into()],
+            vec!["A".into(), "B".into()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            s.aggregate_subset(&[1.0, 2.0], &[true, true]),
+            s.aggregate(&[1.0, 2.0])
+        );
+        assert_eq!(
+            s.aggregate_subset(&[1.0, 2.0], &[false, true]),
+            vec![2.0, 0.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_reconcile_quantiles() {
+        let s = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["Total".into(), "A".into(), "B".into()],
+            vec!["A".into(), "B".into()],
+        )
+        .unwrap();
+        let tol = Tolerance::default();
+        // The 0.9 forecast of B is below its median, so B's quantiles cross
+        let base = vec![
+            (0.5, vec![10.0, 4.0, 5.0]),
+            (0.1, vec![6.0, 2.0, 3.0]),
+            (0.9, vec![12.0, 7.0, 4.0]),
+        ];
+
+        let unsorted = s
+            .reconcile_quantiles(&base, ReconMethod::Ols, false)
+            .unwrap();
+        for ((q, y), (base_q, y_hat)) in unsorted.iter().zip(&base) {
+            assert_eq!(q, base_q);
+            let expected = s.reconcile(y_hat, ReconMethod::Ols).unwrap();
+            assert!(y.iter().zip(&expected).all(|(a, b)| tol.approx_eq(*a, *b)));
+        }
+
+        let sorted = s
+            .reconcile_quantiles(&base, ReconMethod::Ols, true)
+            .unwrap();
+        let (q10, q50, q90) = (&sorted[1].1, &sorted[0].1, &sorted[2].1);
+        for i in 0..3 {
+            assert!(q10[i] <= q50[i] && q50[i] <= q90[i], "series {i}");
+        }
+        assert!(sorted.iter().all(|(_, y)| s.is_coherent(y, tol)));
+
+        assert!(matches!(
+            s.reconcile_quantiles(&[(1.5, vec![1.0, 1.0, 0.0])], ReconMethod::Ols, true),
+            Err(HtsError::Reconciliation(_))
+        ));
+        assert!(matches!(
+            s.reconcile_quantiles(&[(0.5, vec![1.0])], ReconMethod::Ols, true),
+            Err(HtsError::Reconciliation(_))
+        ));
+    }
+
+    #[test]
+    fn test_reconcile_non_negative() {
+        let s = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["Total".into(), "A".into(), "B".into()],
+            vec!["A".into(), "B".into()],
+        )
+        .unwrap();
+        let ones = [1.0; 3];
+        let y_hat = [10.0, 12.0, -1.0];
+        let tol = Tolerance::default();
+
+        let ols = s.reconcile(&y_hat, ReconMethod::Ols).unwrap();
+        assert!(ols[2] < 0.0);
+
+        // With B pinned at zero, A minimizes (10 − a)² + (12 − a)²
+        let result = s
+            .reconcile_non_negative(&y_hat, &ones, IterOpts::default())
+            .unwrap();
+        assert!(result.converged);
+        assert!(result.residual <= IterOpts::default().tol);
+        assert!(tol.approx_eq(result.values[1], 11.0), "{:?}", result.values);
+        assert!(result.values[2] >= 0.0 && result.values[2] < 1e-9);
+        assert!(s.is_coherent(&result.values, tol));
+
+        // Already non-negative: the OLS solution, without iterating
+        let result = s
+            .reconcile_non_negative(&[10.0, 3.0, 5.0], &ones, IterOpts::default())
+            .unwrap();
+        assert_eq!(result.iterations, 0);
+
+        let capped = IterOpts {
+            max_iter: 1,
+            tol: 1e-15,
+            ..Default::default()
+        };
+        assert!(matches!(
+            s.reconcile_non_negative(&y_hat, &ones, capped),
+            Err(HtsError::Reconciliation(_))
+        ));
+        let lenient = IterOpts {
+            fail_on_max_iter: false,
+            ..capped
+        };
+        let result = s.reconcile_non_negative(&y_hat, &ones, lenient).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 1);
+    }
+
+    #[test]
+    fn test_verify_structure() {
+        let labels = || vec!["Total".to_string(), "A".into(), "B".into()];
+        let cols = || vec!["A".to_string(), "B".into()];
+        let s =
+            SummationMatrix::from_triplets(&[0, 0, 1, 2], &[0, 1, 0, 1], labels(), cols()).unwrap();
+        assert!(s.verify_structure().is_ok());
+        assert!(
+            SummationMatrix::temporal(4)
+                .unwrap()
+                .verify_structure()
+                .is_ok()
+        );
+
+        // B has no row of its own
+        let s = SummationMatrix::from_triplets(&[0, 0, 1], &[0, 1, 0], labels(), cols()).unwrap();
+        let err = s.verify_structure().unwrap_err();
+        assert!(
+            matches!(&err, HtsError::Hierarchy(msg) if msg.contains("'B'")),
+            "{err}"
+        );
+
+        let s =
+            SummationMatrix::from_triplets(&[0, 0, 1, 2], &[0, 1, 0, 0], labels(), cols()).unwrap();
+        let err = s.verify_structure().unwrap_err();
+        assert!(
+            matches!(&err, HtsError::Hierarchy(msg) if msg.contains("duplicates")),
+            "{err}"
+        );
+
+        // The first row misses B
+        let s = SummationMatrix::from_triplets(&[0, 1, 2], &[0, 0, 1], labels(), cols()).unwrap();
+        assert!(matches!(s.verify_structure(), Err(HtsError::Hierarchy(_))));
+    }
+
+    #[test]
+    fn test_reconcile_with_anchors() {
+        let s = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["Total".into(), "A".into(), "B".into()],
+            vec!["A".into(), "B".into()],
+        )
+        .unwrap();
+        let y_hat = [10.0, 3.0, 5.0];
+        let tol = Tolerance::default();
+
+        let y = s
+            .reconcile_with_anchors(&y_hat, &[("Total".into(), 12.0)])
+            .unwrap();
+        assert!(tol.approx_eq(y[0], 12.0));
+        assert!(s.is_coherent(&y, tol));
+        // The adjustment is shared equally between A and B
+        assert!(tol.approx_eq(y[1] - y_hat[1], y[2] - y_hat[2]));
+
+        // Without anchors this is plain OLS
+        let ols = s.reconcile(&y_hat, ReconMethod::Ols).unwrap();
+        let free = s.reconcile_with_anchors(&y_hat, &[]).unwrap();
+        assert!(ols.iter().zip(&free).all(|(a, b)| tol.approx_eq(*a, *b)));
+
+        // Redundant but consistent anchors are fine
+        let anchors = [("Total".into(), 9.0), ("A".into(), 4.0), ("B".into(), 5.0)];
+        let y = s.reconcile_with_anchors(&y_hat, &anchors).unwrap();
+        assert!(tol.approx_eq(y[1], 4.0));
+
+        let anchors = [("Total".into(), 9.0), ("A".into(), 4.0), ("B".into(), 6.0)];
+        assert!(matches!(
+            s.reconcile_with_anchors(&y_hat, &anchors),
+            Err(HtsError::Reconciliation(_))
+        ));
+        assert!(matches!(
+            s.reconcile_with_anchors(&y_hat, &[("C".into(), 1.0)]),
+            Err(HtsError::Hierarchy(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_residuals() {
+        let s = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["Total".into(), "A".into(), "B".into()],
+            vec!["A".into(), "B".into()],
+        )
+        .unwrap();
+
+        assert!(s.validate_residuals(&Mat::zeros(3, 4)).is_ok());
+
+        let err = s.validate_residuals(&Mat::zeros(2, 4)).unwrap_err();
+        assert!(matches!(err, HtsError::Reconciliation(_)));
+        assert!(err.to_string().contains("3 series"), "{err}");
+
+        assert!(matches!(
+            s.validate_residuals(&Mat::zeros(3, 1)),
+            Err(HtsError::Reconciliation(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_coherent() {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+
+        let mut y = s.aggregate(&[1e9, 2e9, 3e9, 4e9]);
+        assert!(s.is_coherent(&y, Tolerance::default()));
+
+        // Off by 1 on a total of 1e10: within the default relative bound
+        y[0] += 1.0;
+        assert!(s.is_coherent(&y, Tolerance::default()));
+        assert!(!s.is_coherent(&y, Tolerance::new(1e-6, 0.0)));
+
+        y[0] += 1e3;
+        assert!(!s.is_coherent(&y, Tolerance::default()));
+        assert_eq!(s.coherence_residual(&y), 1001.0);
+
+        // Bottom rows define the target and never count
+        y[0] -= 1001.0;
+        y[2] -= 5.0;
+        assert_eq!(s.coherence_residual(&y), 5.0);
+    }
+
+    #[test]
+    fn test_bottom_and_aggregation_blocks() {
+        let df = df! {
+            "State" => ["A", "A", "A", "A", "B", "B", "B", "B"],
+            "Region" => ["AA", "AA", "AB", "AB", "BA", "BA", "BB", "BB"],
+            "Purpose" => ["X", "Y", "X", "Y", "X", "Y", "X", "Y"],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::new(
+            vec!["State".into(), "Region".into()],
+            vec!["Purpose".into()],
+        );
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+        let (n, m) = s.shape();
+
+        let bottom = s.bottom_block();
+        assert_eq!((bottom.nrows(), bottom.ncols()), (m, m));
+        for i in 0..m {
+            for j in 0..m {
+                assert_eq!(bottom[(i, j)], if i == j { 1.0 } else { 0.0 });
+            }
+        }
+        assert_eq!(&s.row_labels()[n - m..], s.col_labels());
+
+        let agg = s.aggregation_block();
+        assert_eq!((agg.nrows(), agg.ncols()), (n - m, m));
+        // Total row aggregates every bottom series
+        assert!((0..m).all(|j| agg[(0, j)] == 1.0));
+    }
+
+    #[test]
+    fn test_row_and_col_sums() {
+        let df = df! {
+            "State" => ["A", "A", "A", "B"],
+            "Region" => ["AA", "AA", "AB", "BA"],
+            "City" => ["X", "Y", "Z", "W"],
+        }
+        .unwrap();
+        let spec =
+            HierarchySpec::hierarchical(vec!["State".into(), "Region".into(), "City".into()]);
+        let s =
+            SummationMatrix::from_hierarchy(&HierarchyTree::from_dataframe(&df, &spec).unwrap());
+
+        // Total, A, B, A/AA, A/AB, B/BA, then the 4 cities
+        assert_eq!(
+            s.row_sums(),
+            [4.0, 3.0, 1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]
+        );
+        assert_eq!(s.col_sums(), [4.0; 4]);
+
+        let temporal = SummationMatrix::temporal(4).unwrap();
+        assert_eq!(temporal.row_sums(), [4.0, 2.0, 2.0, 1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(temporal.col_sums(), [3.0; 4]);
+
+        let triplets = SummationMatrix::from_triplets(
+            &[0, 0, 1, 2],
+            &[0, 1, 0, 1],
+            vec!["T".into(), "a".into(), "b".into()],
+            vec!["a".into(), "b".into()],
+        )
+        .unwrap();
+        assert_eq!(triplets.row_sums(), [2.0, 1.0, 1.0]);
+        assert_eq!(triplets.col_sums(), [2.0, 2.0]);
+
+        // The sums follow edits of the public matrix
+        let mut edited = triplets.clone();
+        edited.matrix[(0, 1)] = 0.0;
+        assert_eq!(edited.row_sums(), [1.0, 1.0, 1.0]);
+        assert_eq!(edited.col_sums(), [2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_triplets_roundtrip() {
+        let df = df! {
+            "State" => ["A", "A", "B", "B"],
+            "Region" => ["AA", "AB", "BA", "BB"],
+        }
+        .unwrap();
+
+        let spec = HierarchySpec::hierarchical(vec!["State".into(), "Region".into()]);
+        let tree = HierarchyTree::from_dataframe(&df, &spec).unwrap();
+        let s = SummationMatrix::from_hierarchy(&tree);
+
+        let (rows, cols) = s.to_triplets();
+        // Total (4) + A (2) + B (2) + bottom (4)
+        assert_eq!(rows.len(), 12);
+        let rebuilt = SummationMatrix::from_triplets(
+            &rows,
+            &cols,
+            s.row_labels.clone(),
+            s.col_labels.clone(),
+        )
+        .unwrap();
+        assert_eq!(rebuilt.to_vec(), s.to_vec());
+
+        let mut buf = Vec::new();
+        s.write_triplets(&mut buf).unwrap();
+        let read = SummationMatrix::read_triplets(buf.as_slice()).unwrap();
+        assert_eq!(read.to_vec(), s.to_vec());
+        assert_eq!(read.row_labels(), s.row_labels());
+        assert_eq!(read.col_labels(), s.col_labels());
+
+        assert!(SummationMatrix::from_triplets(&[9], &[0], vec![], vec![]).is_err());
+        assert!(SummationMatrix::read_triplets(&b"nope"[..]).is_err());
+
+        // A header claiming huge sizes fails on the short input
+        let mut hostile = TRIPLET_MAGIC.to_vec();
+        hostile.extend(TRIPLET_VERSION.to_le_bytes());
+        hostile.extend(u32::MAX.to_le_bytes());
+        hostile.extend(u32::MAX.to_le_bytes());
+        hostile.extend(u64::MAX.to_le_bytes());
+        assert!(SummationMatrix::read_triplets(hostile.as_slice()).is_err());
+
+        // A small header for a 50k × 50k matrix is refused before allocating
+        let mut dense = TRIPLET_MAGIC.to_vec();
+        dense.extend(TRIPLET_VERSION.to_le_bytes());
+        dense.extend(50_000u32.to_le_bytes());
+        dense.extend(50_000u32.to_le_bytes());
+        dense.extend(0u64.to_le_bytes());
+        assert!(matches!(
+            SummationMatrix::read_triplets(dense.as_slice()),
+            Err(HtsError::Hierarchy(_))
+        ));
+        let labels = vec![String::new(); 50_000];
+        assert!(matches!(
+            SummationMatrix::from_triplets(&[], &[], labels.clone(), labels),
+            Err(HtsError::Hierarchy(_))
+        ));
+
+        // So does a label longer than the remaining input
+        let mut truncated = buf.clone();
+        let last = truncated.len() - s.col_labels()[3].len() - 4;
+        truncated.truncate(last);
+        truncated.extend(u32::MAX.to_le_bytes());
+        assert!(SummationMatrix::read_triplets(truncated.as_slice()).is_err());
+    }
 }
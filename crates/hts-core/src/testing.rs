@@ -0,0 +1,271 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Synthetic data generators for tests, benchmarks, and demos.
+//!
+//! Generated data is fully deterministic given the seed, so it is safe to use
+//! in reproducible tests. The generator does not depend on an external random
+//! number crate, which keeps the output stable across dependency upgrades.
+
+use crate::hierarchy::HierarchySpec;
+use crate::hts::HierarchicalTimeSeries;
+use crate::period::Period;
+use crate::summation_matrix::SummationMatrix;
+use polars::prelude::*;
+
+/// Name of the time column in generated data.
+pub const TIME_COL: &str = "Period";
+
+/// Name of the value column in generated data.
+pub const VALUE_COL: &str = "Value";
+
+/// Small deterministic pseudo-random number generator (SplitMix64).
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value uniformly distributed in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Generates fully-populated synthetic bottom-level data.
+///
+/// # Arguments
+///
+/// * `levels` - Branching factor of each hierarchical level, top to bottom.
+///   `[2, 3]` yields 2 nodes at `Level1`, each with 3 children at `Level2`.
+/// * `groups` - Number of categories of each grouping column
+///   (`Group1`, `Group2`, ...).
+/// * `periods` - Number of quarterly periods, starting at 2000 Q1.
+/// * `seed` - Seed for the value generator.
+///
+/// # Returns
+///
+/// The bottom-level DataFrame (with [`TIME_COL`] and [`VALUE_COL`]) and the
+/// matching `HierarchySpec`. Every bottom series is observed in every period.
+pub fn random_bottom_data(
+    levels: &[usize],
+    groups: &[usize],
+    periods: usize,
+    seed: u64,
+) -> (DataFrame, HierarchySpec) {
+    let hier_names: Vec<String> = (1..=levels.len()).map(|i| format!("Level{i}")).collect();
+    let group_names: Vec<String> = (1..=groups.len()).map(|i| format!("Group{i}")).collect();
+
+    // Hierarchy paths: each child label extends its parent's label so that
+    // values are unique within their column.
+    let mut paths: Vec<Vec<String>> = vec![Vec::new()];
+    for &branching in levels {
+        paths = paths
+            .iter()
+            .flat_map(|path| {
+                (1..=branching).map(move |i| {
+                    let label = match path.last() {
+                        Some(parent) => format!("{parent}-{i}"),
+                        None => format!("{i}"),
+                    };
+                    let mut child = path.clone();
+                    child.push(label);
+                    child
+                })
+            })
+            .collect();
+    }
+
+    // Crossed group categories
+    let mut crossings: Vec<Vec<String>> = vec![Vec::new()];
+    for (g, &n_categories) in groups.iter().enumerate() {
+        crossings = crossings
+            .iter()
+            .flat_map(|combo| {
+                (1..=n_categories).map(move |i| {
+                    let mut next = combo.clone();
+                    next.push(format!("G{}-{i}", g + 1));
+                    next
+                })
+            })
+            .collect();
+    }
+
+    let time_labels: Vec<String> = (0..periods)
+        .map(|t| Period::Quarterly(2000 + (t / 4) as i32, (t % 4) as u8 + 1).to_string())
+        .collect();
+
+    let mut key_values: Vec<Vec<String>> = vec![Vec::new(); levels.len() + groups.len()];
+    let mut time_values = Vec::new();
+    let mut values = Vec::new();
+    let mut rng = SplitMix64(seed);
+
+    for path in &paths {
+        for combo in &crossings {
+            for time in &time_labels {
+                for (k, label) in path.iter().chain(combo.iter()).enumerate() {
+                    key_values[k].push(label.clone());
+                }
+                time_values.push(time.clone());
+                values.push((rng.next_f64() * 100.0 * 100.0).round() / 100.0);
+            }
+        }
+    }
+
+    let mut columns: Vec<Column> = hier_names
+        .iter()
+        .chain(group_names.iter())
+        .zip(key_values)
+        .map(|(name, vals)| Column::new(name.as_str().into(), vals))
+        .collect();
+    columns.push(Column::new(TIME_COL.into(), time_values));
+    columns.push(Column::new(VALUE_COL.into(), values));
+
+    let df = DataFrame::new(columns).expect("generated columns have equal length");
+    let spec = HierarchySpec::new(hier_names, group_names);
+
+    (df, spec)
+}
+
+/// Builds a fully-populated synthetic hierarchical time series.
+///
+/// This is a convenience wrapper around [`random_bottom_data`] that loads the
+/// generated data into a `HierarchicalTimeSeries`. The result is deterministic
+/// given `seed`.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::testing::random_hierarchy;
+///
+/// // 3 states with 4 regions each, crossed with 2 purposes, over 8 quarters
+/// let hts = random_hierarchy(&[3, 4], &[2], 8, 42);
+///
+/// assert_eq!(hts.n_bottom(), 24);
+/// assert_eq!(hts.n_periods(), 8);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `periods` is zero.
+pub fn random_hierarchy(
+    levels: &[usize],
+    groups: &[usize],
+    periods: usize,
+    seed: u64,
+) -> HierarchicalTimeSeries {
+    assert!(periods > 0, "At least one period is required");
+
+    let (df, spec) = random_bottom_data(levels, groups, periods, seed);
+    HierarchicalTimeSeries::new(df, spec, TIME_COL, VALUE_COL)
+        .expect("generated data is a valid hierarchy")
+}
+
+/// Generates incoherent but plausible base forecasts for every series of
+/// `s`, in row order.
+///
+/// Random bottom-level values in `[10, 110)` are aggregated through `s`, then
+/// every row, bottom rows included, is scaled by its own random factor in
+/// `[0.9, 1.1)`. The result is close to coherent, as real base forecasts
+/// are, but does not satisfy `s`; reconciling it should bring
+/// [`SummationMatrix::coherence_residual`] down to rounding error. The
+/// result is deterministic given `seed`.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::ReconMethod;
+/// use hts_core::testing::{incoherent_base, random_hierarchy};
+///
+/// let hts = random_hierarchy(&[2, 3], &[], 1, 42);
+/// let s = hts.summation_matrix();
+///
+/// let base = incoherent_base(s, 7);
+/// assert!(s.coherence_residual(&base) > 1e-3);
+///
+/// let reconciled = s.reconcile(&base, ReconMethod::Ols).unwrap();
+/// assert!(s.coherence_residual(&reconciled) < 1e-9);
+/// ```
+pub fn incoherent_base(s: &SummationMatrix, seed: u64) -> Vec<f64> {
+    let mut rng = SplitMix64(seed);
+    let bottom: Vec<f64> = (0..s.n_bottom())
+        .map(|_| 10.0 + rng.next_f64() * 100.0)
+        .collect();
+
+    s.aggregate(&bottom)
+        .into_iter()
+        .map(|value| value * (0.9 + rng.next_f64() * 0.2))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reconciliation::ReconMethod;
+
+    #[test]
+    fn test_random_bottom_data_shape() {
+        let (df, spec) = random_bottom_data(&[2, 3], &[2], 4, 42);
+
+        // 2 * 3 regions × 2 categories × 4 periods
+        assert_eq!(df.height(), 48);
+        assert_eq!(spec.hierarchy, vec!["Level1", "Level2"]);
+        assert_eq!(spec.groups, vec!["Group1"]);
+        assert!(spec.validate(&df).is_ok());
+    }
+
+    #[test]
+    fn test_random_hierarchy_deterministic() {
+        let a = random_hierarchy(&[2, 2], &[3], 5, 7);
+        let b = random_hierarchy(&[2, 2], &[3], 5, 7);
+        let c = random_hierarchy(&[2, 2], &[3], 5, 8);
+
+        assert_eq!(a.n_bottom(), 12);
+        assert_eq!(a.n_periods(), 5);
+        assert!(a.bottom_data().equals(b.bottom_data()));
+        assert!(!a.bottom_data().equals(c.bottom_data()));
+    }
+
+    #[test]
+    fn test_incoherent_base() {
+        let hts = random_hierarchy(&[2, 2], &[3], 1, 7);
+        let s = hts.summation_matrix();
+
+        let base = incoherent_base(s, 1);
+        assert_eq!(base.len(), s.n_series());
+        assert_eq!(base, incoherent_base(s, 1));
+        assert_ne!(base, incoherent_base(s, 2));
+        assert!(base.iter().all(|&v| v > 0.0));
+
+        let before = s.coherence_residual(&base);
+        assert!(before > 1.0);
+        for method in [
+            ReconMethod::BottomUp,
+            ReconMethod::Ols,
+            ReconMethod::WlsStructural,
+        ] {
+            let reconciled = s.reconcile(&base, method).unwrap();
+            assert!(s.coherence_residual(&reconciled) < 1e-9 * before);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Numeric tolerances for comparing floating-point values.
+//!
+//! Aggregated values accumulate rounding error, so coherence checks compare
+//! with a tolerance rather than exactly. A `Tolerance` combines an absolute
+//! and a relative bound: two values `a` and `b` are considered equal when
+//!
+//! $$|a - b| \le \text{abs} + \text{rel} \cdot \max(|a|, |b|)$$
+//!
+//! The absolute bound dominates near zero, and the relative bound dominates
+//! for large magnitudes.
+
+/// Absolute and relative tolerance pair used in floating-point comparisons.
+///
+/// # Example
+///
+/// ```
+/// use hts_core::Tolerance;
+///
+/// let tol = Tolerance::default();
+/// assert!(tol.approx_eq(1e12, 1e12 + 100.0));
+/// assert!(!tol.approx_eq(1e-3, 2e-3));
+///
+/// let strict = Tolerance::new(0.0, 1e-12);
+/// assert!(!strict.approx_eq(1e12, 1e12 + 100.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Absolute tolerance.
+    pub abs: f64,
+
+    /// Relative tolerance, scaled by the larger magnitude of the two values.
+    pub rel: f64,
+}
+
+impl Tolerance {
+    /// Creates a tolerance from an absolute and a relative bound.
+    pub fn new(abs: f64, rel: f64) -> Self {
+        Self { abs, rel }
+    }
+
+    /// Returns true if `a` and `b` are equal within this tolerance.
+    ///
+    /// NaN is never equal to anything.
+    pub fn approx_eq(&self, a: f64, b: f64) -> bool {
+        (a - b).abs() <= self.abs + self.rel * a.abs().max(b.abs())
+    }
+}
+
+impl Default for Tolerance {
+    /// Returns `abs = 1e-6`, `rel = 1e-9`.
+    fn default() -> Self {
+        Self {
+            abs: 1e-6,
+            rel: 1e-9,
+        }
+    }
+}
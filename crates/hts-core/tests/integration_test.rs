@@ -17,7 +17,8 @@
 
 //! Integration tests for HTS-Core using real tourism data.
 
-use hts_core::{HierarchicalTimeSeries, HierarchySpec};
+use hts_core::interop::compare_aggregations;
+use hts_core::{HierarchicalTimeSeries, HierarchySpec, Tolerance};
 use polars::prelude::*;
 use std::path::PathBuf;
 
@@ -36,6 +37,7 @@ fn aggregated_csv() -> PathBuf {
 }
 
 /// Load the R aggregated CSV (semicolon-delimited with Brazilian decimal notation).
+#[allow(clippy::useless_conversion)]
 fn load_aggregated_df() -> PolarsResult<DataFrame> {
     CsvReadOptions::default()
         .with_has_header(true)
@@ -88,10 +90,14 @@ fn test_summation_matrix_shape() {
     // n = total series, m = bottom-level series
     assert_eq!(n, hts.n_series());
     assert_eq!(m, hts.n_bottom());
+    // ACT has a single region, so some rows repeat across levels
+    s.verify_structure()
+        .expect("S should have a valid structure");
 }
 
 /// Validate total aggregation matches R's output.
 #[test]
+#[allow(clippy::useless_conversion)]
 fn test_total_aggregation() {
     // Load bottom-level data
     let bottom_df = CsvReadOptions::default()
@@ -206,42 +212,24 @@ fn test_full_aggregation_match() {
 
     assert_eq!(rust_agg.height(), r_agg.height(), "Row counts should match");
 
-    // Sort both by all key columns + Quarter to compare
-    let sort_cols = vec!["State", "Region", "Purpose", "Quarter"];
-
-    let rust_sorted = rust_agg
-        .sort(sort_cols.clone(), SortMultipleOptions::default())
-        .unwrap();
-
-    let r_sorted = r_agg
-        .sort(sort_cols, SortMultipleOptions::default())
-        .unwrap();
-
-    // Check first 5 rows match
-    let sub_rust = rust_sorted.head(Some(5));
-    let sub_r = r_sorted.head(Some(5));
-
-    println!("Rust head:\n{}", sub_rust);
-    println!("R head:\n{}", sub_r);
-
-    // Check totals
-    let rust_sum: f64 = rust_sorted
-        .column("Trips")
-        .unwrap()
-        .f64()
-        .unwrap()
-        .sum()
-        .unwrap();
-    let r_sum: f64 = r_sorted
-        .column("Trips")
-        .unwrap()
-        .f64()
-        .unwrap()
-        .sum()
-        .unwrap();
+    let report = compare_aggregations(
+        &rust_agg,
+        &r_agg,
+        &["State", "Region", "Purpose", "Quarter"],
+        "Trips",
+        Tolerance::new(1e-6, 1e-9),
+    )
+    .unwrap();
+
+    println!(
+        "First mismatches: {:?}",
+        &report.mismatches[..report.mismatches.len().min(5)]
+    );
 
+    assert_eq!(report.n_matched, r_agg.height(), "Every R row should match");
+    assert!(report.is_clean(), "Aggregates should match R's");
     assert!(
-        (rust_sum - r_sum).abs() < 1.0,
+        (report.rust_total - report.r_total).abs() < 1.0,
         "Total sum across all series should match"
     );
 }
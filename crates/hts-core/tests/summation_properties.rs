@@ -0,0 +1,145 @@
+// Copyright (C) 2026 Alberson Miranda
+//
+// This file is part of hts-rs.
+//
+// hts-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// hts-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with hts-rs.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Property tests for the summation matrix.
+//!
+//! The core contract of the crate: for every period, `S * b` reproduces the
+//! per-node sums obtained by grouping the bottom-level data by each level's
+//! columns.
+
+use hts_core::HierarchicalTimeSeries;
+use hts_core::testing::{TIME_COL, VALUE_COL, random_hierarchy};
+use polars::prelude::*;
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+/// Joins the string values of `cols` at `row` into a series id.
+fn row_id(df: &DataFrame, cols: &[String], row: usize, total_label: &str) -> String {
+    if cols.is_empty() {
+        return total_label.to_string();
+    }
+    cols.iter()
+        .map(|c| {
+            df.column(c)
+                .unwrap()
+                .str()
+                .unwrap()
+                .get(row)
+                .unwrap()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Computes every node's sum for one period directly with Polars group-bys.
+fn brute_force_sums(hts: &HierarchicalTimeSeries, period: &str) -> HashMap<String, f64> {
+    let bottom = hts
+        .bottom_data()
+        .clone()
+        .lazy()
+        .filter(col(TIME_COL).eq(lit(period)));
+
+    let mut sums = HashMap::new();
+    for level_cols in hts.spec().level_combinations() {
+        let keys: Vec<Expr> = level_cols.iter().map(|c| col(c.as_str())).collect();
+        let df = if keys.is_empty() {
+            bottom.clone().select([col(VALUE_COL).sum()])
+        } else {
+            bottom.clone().group_by(keys).agg([col(VALUE_COL).sum()])
+        }
+        .collect()
+        .unwrap();
+
+        let values = df.column(VALUE_COL).unwrap().f64().unwrap();
+        for row in 0..df.height() {
+            sums.insert(
+                row_id(&df, &level_cols, row, &hts.spec().total_label),
+                values.get(row).unwrap(),
+            );
+        }
+    }
+    sums
+}
+
+/// Extracts the bottom vector for one period in S column order.
+fn bottom_vector(hts: &HierarchicalTimeSeries, period: &str) -> Vec<f64> {
+    let df = hts
+        .bottom_data()
+        .clone()
+        .lazy()
+        .filter(col(TIME_COL).eq(lit(period)))
+        .collect()
+        .unwrap();
+    let all_cols: Vec<String> = hts
+        .spec()
+        .all_columns()
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let values = df.column(VALUE_COL).unwrap().f64().unwrap();
+
+    let by_id: HashMap<String, f64> = (0..df.height())
+        .map(|row| (row_id(&df, &all_cols, row, ""), values.get(row).unwrap()))
+        .collect();
+
+    hts.summation_matrix()
+        .col_labels()
+        .iter()
+        .map(|id| by_id[id])
+        .collect()
+}
+
+/// Random hierarchies of up to three levels crossed with up to two groups.
+///
+/// Branching factors of 1 produce single-child chains, where an aggregate
+/// sums exactly one bottom series.
+fn hierarchy_shape() -> impl Strategy<Value = (Vec<usize>, Vec<usize>, usize, u64)> {
+    (
+        prop::collection::vec(1usize..=3, 1..=3),
+        prop::collection::vec(1usize..=3, 0..=2),
+        1usize..=3,
+        any::<u64>(),
+    )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn s_times_b_matches_group_by_sums((levels, groups, periods, seed) in hierarchy_shape()) {
+        let hts = random_hierarchy(&levels, &groups, periods, seed);
+        let s = hts.summation_matrix();
+        prop_assert!(s.verify_structure().is_ok(), "{:?}", s.verify_structure());
+
+        for period in hts.periods() {
+            let period = period.to_string();
+            let y = s.aggregate(&bottom_vector(&hts, &period));
+            let expected = brute_force_sums(&hts, &period);
+
+            prop_assert_eq!(expected.len(), s.n_series());
+            for (row, id) in s.row_labels().iter().enumerate() {
+                let want = expected[id];
+                prop_assert!(
+                    (y[row] - want).abs() <= 1e-9 * want.abs().max(1.0),
+                    "{} in {}: S * b = {}, group-by sum = {}",
+                    id, period, y[row], want
+                );
+            }
+        }
+    }
+}